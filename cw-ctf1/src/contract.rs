@@ -1,20 +1,41 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, BalanceResponse, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
-    Response, StdError, StdResult, Uint128,
+    to_binary, Addr, BalanceResponse, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut,
+    Env, Fraction, MessageInfo, Reply, Response, StdError, StdResult, Storage, SubMsg,
+    SubMsgResult, Uint128, WasmMsg,
 };
 
+use cw2::{set_contract_version, CONTRACT};
+
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::USER_BALANCE;
+use crate::helpers::{add_prefixed_event, update_balance, validate_event_prefix, BalanceDelta};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
+use crate::state::{
+    ADMIN, ALLOW_WITHDRAW_WHEN_PAUSED, CHAIN_PREFIX, COLLECT_DUST, DENOM, DUST_POOL,
+    DUST_THRESHOLD, EVENT_PREFIX, MAX_BALANCE, PAUSED, PENDING_VESTING, USER_BALANCE,
+};
+
+/// version info for migration guards
+const CONTRACT_NAME: &str = "crates.io:cw-ctf1";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// bounds on a native denom length, matching the sdk denom rules
+const MIN_DENOM_LENGTH: usize = 3;
+const MAX_DENOM_LENGTH: usize = 128;
+
+/// maximum number of recipients in a single batch deposit
+const MAX_ALLOCATIONS: usize = 50;
+
+/// reply id for the vesting contract execution
+const VESTING_REPLY_ID: u64 = 1;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     // admin must provide 1000 uosmo when instantiating contract
     if info.funds.len() != 1
@@ -26,6 +47,38 @@ pub fn instantiate(
         )));
     }
 
+    // admin defaults to ourself, a factory may set another one
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender.clone(),
+    };
+    ADMIN.save(deps.storage, &admin)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    DENOM.save(deps.storage, &"uosmo".to_string())?;
+
+    // users can withdraw while paused unless disabled
+    PAUSED.save(deps.storage, &false)?;
+    ALLOW_WITHDRAW_WHEN_PAUSED.save(
+        deps.storage,
+        &msg.allow_withdraw_when_paused.unwrap_or(true),
+    )?;
+
+    // per-address cap, unlimited by default
+    MAX_BALANCE.save(deps.storage, &msg.max_balance.unwrap_or_default())?;
+
+    // dust collection is disabled by default
+    DUST_THRESHOLD.save(deps.storage, &msg.dust_threshold.unwrap_or_default())?;
+    COLLECT_DUST.save(deps.storage, &msg.collect_dust.unwrap_or_default())?;
+
+    // optional recipient prefix check
+    CHAIN_PREFIX.save(deps.storage, &msg.chain_prefix.unwrap_or_default())?;
+
+    // optional prefix for custom events
+    if let Some(event_prefix) = &msg.event_prefix {
+        validate_event_prefix(event_prefix)?;
+    }
+    EVENT_PREFIX.save(deps.storage, &msg.event_prefix.unwrap_or_default())?;
+
     Ok(Response::new())
 }
 
@@ -36,29 +89,98 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    match msg {
+    let event_prefix = EVENT_PREFIX.may_load(deps.storage)?.unwrap_or_default();
+
+    check_paused(deps.storage, &msg)?;
+
+    let res = match msg {
         ExecuteMsg::Deposit {} => try_deposit(deps, info),
         ExecuteMsg::Withdraw { amount } => try_withdraw(deps, info, amount),
+        ExecuteMsg::WithdrawTo { recipient, amount } => {
+            try_withdraw_to(deps, info, recipient, amount)
+        }
+        ExecuteMsg::BatchDeposit { allocations } => try_batch_deposit(deps, info, allocations),
+        ExecuteMsg::WithdrawToVesting {
+            amount,
+            vesting_contract,
+            msg,
+        } => try_withdraw_to_vesting(deps, info, amount, vesting_contract, msg),
+        ExecuteMsg::SetPaused { paused } => try_set_paused(deps, info, paused),
+        ExecuteMsg::WithdrawDust {} => try_withdraw_dust(deps, info),
+    }?;
+
+    Ok(add_prefixed_event(res, &event_prefix))
+}
+
+/// block deposits while paused, and withdrawals too unless allowed
+fn check_paused(storage: &dyn Storage, msg: &ExecuteMsg) -> Result<(), ContractError> {
+    if !PAUSED.may_load(storage)?.unwrap_or_default() {
+        return Ok(());
+    }
+
+    let allow_withdraw = ALLOW_WITHDRAW_WHEN_PAUSED
+        .may_load(storage)?
+        .unwrap_or(true);
+    match msg {
+        ExecuteMsg::SetPaused { .. } => Ok(()),
+        ExecuteMsg::Withdraw { .. }
+        | ExecuteMsg::WithdrawTo { .. }
+        | ExecuteMsg::WithdrawToVesting { .. }
+            if allow_withdraw =>
+        {
+            Ok(())
+        }
+        _ => Err(ContractError::Paused {}),
+    }
+}
+
+/// reject recipients that cannot exist on this chain
+fn check_chain_prefix(storage: &dyn Storage, recipient: &Addr) -> Result<(), ContractError> {
+    let chain_prefix = CHAIN_PREFIX.may_load(storage)?.unwrap_or_default();
+    if !chain_prefix.is_empty()
+        && !recipient
+            .as_str()
+            .starts_with(&format!("{}1", chain_prefix))
+    {
+        return Err(ContractError::WrongChainPrefix {});
+    }
+    Ok(())
+}
+
+/// reject credits that would take `address` above the configured cap
+fn check_balance_cap(
+    storage: &dyn Storage,
+    address: &Addr,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let cap = MAX_BALANCE.may_load(storage)?.unwrap_or_default();
+    if cap.is_zero() {
+        return Ok(());
+    }
+
+    let balance = USER_BALANCE.may_load(storage, address)?.unwrap_or_default();
+    match balance.checked_add(amount) {
+        Ok(balance) if balance <= cap => Ok(()),
+        _ => Err(ContractError::CapExceeded { cap }),
     }
 }
 
 pub fn try_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
-    // validate user deposit to uosmo
+    // validate user deposit to vault denom
+    let denom = load_denom(deps.storage)?;
     info.funds
         .iter()
-        .find(|c| c.denom == "uosmo")
+        .find(|c| c.denom == denom)
         .map(|c| c.amount)
         .expect("Invalid deposit!");
 
     // update user balance
-    USER_BALANCE.update(
+    check_balance_cap(deps.storage, &info.sender, info.funds[0].amount)?;
+    update_balance(
         deps.storage,
+        &USER_BALANCE,
         &info.sender,
-        |balance: Option<Uint128>| -> StdResult<_> {
-            Ok(balance
-                .unwrap_or_default()
-                .checked_add(info.funds[0].amount)?)
-        },
+        BalanceDelta::Add(info.funds[0].amount),
     )?;
 
     Ok(Response::new()
@@ -66,32 +188,348 @@ pub fn try_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, Contrac
         .add_attribute("amount", info.funds[0].amount))
 }
 
+pub fn try_batch_deposit(
+    deps: DepsMut,
+    info: MessageInfo,
+    allocations: Vec<(String, Uint128)>,
+) -> Result<Response, ContractError> {
+    // bound gas usage
+    if allocations.len() > MAX_ALLOCATIONS {
+        return Err(ContractError::TooManyAllocations {});
+    }
+
+    // validate vault denom sent
+    if info.funds.len() != 1 || info.funds[0].denom != load_denom(deps.storage)? {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Invalid deposit!",
+        )));
+    }
+
+    // allocations must add up to the deposited amount
+    let total = allocations
+        .iter()
+        .try_fold(Uint128::zero(), |acc, (_, amount)| acc.checked_add(*amount))
+        .map_err(StdError::from)?;
+    if total != info.funds[0].amount {
+        return Err(ContractError::AllocationMismatch {});
+    }
+
+    // credit each recipient
+    for (recipient, amount) in &allocations {
+        let recipient = deps.api.addr_validate(recipient)?;
+        check_balance_cap(deps.storage, &recipient, *amount)?;
+        update_balance(
+            deps.storage,
+            &USER_BALANCE,
+            &recipient,
+            BalanceDelta::Add(*amount),
+        )?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "batch_deposit")
+        .add_attribute("amount", total)
+        .add_attribute("recipients", allocations.len().to_string()))
+}
+
 pub fn try_withdraw(
     deps: DepsMut,
     info: MessageInfo,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
-    // decrease user balance
-    USER_BALANCE.update(
+    let (msg, new_balance) = debit_and_send(deps, &info.sender, &info.sender, amount)?;
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("method", "withdraw")
+        .add_attribute("amount", amount)
+        .add_attribute("new_balance", new_balance))
+}
+
+pub fn try_withdraw_to(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    check_chain_prefix(deps.storage, &recipient)?;
+    let (msg, new_balance) = debit_and_send(deps, &info.sender, &recipient, amount)?;
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("method", "withdraw_to")
+        .add_attribute("amount", amount)
+        .add_attribute("new_balance", new_balance)
+        .add_attribute("recipient", recipient))
+}
+
+pub fn try_set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    // verify sender is admin
+    if info.sender != ADMIN.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    PAUSED.save(deps.storage, &paused)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_paused")
+        .add_attribute("paused", paused.to_string()))
+}
+
+pub fn try_withdraw_dust(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    // verify sender is admin
+    if info.sender != ADMIN.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let dust_pool = DUST_POOL.may_load(deps.storage)?.unwrap_or_default();
+    if dust_pool.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    DUST_POOL.save(deps.storage, &Uint128::zero())?;
+
+    // send swept dust to admin
+    let msg = CosmosMsg::Bank(BankMsg::Send {
+        to_address: info.sender.to_string(),
+        amount: vec![Coin {
+            denom: load_denom(deps.storage)?,
+            amount: dust_pool,
+        }],
+    });
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("method", "withdraw_dust")
+        .add_attribute("amount", dust_pool))
+}
+
+pub fn try_withdraw_to_vesting(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+    vesting_contract: String,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let vesting_contract = deps.api.addr_validate(&vesting_contract)?;
+    let balance = USER_BALANCE
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    let new_balance = debit(deps.storage, &info.sender, amount)?;
+    // any remainder missing after the debit was swept into the dust pool
+    let dust = balance - amount - new_balance;
+
+    // remember the debit in case the vesting contract rejects the funds
+    PENDING_VESTING.save(deps.storage, &(info.sender.clone(), amount, dust))?;
+
+    // forward funds together with the vesting message
+    let msg = WasmMsg::Execute {
+        contract_addr: vesting_contract.to_string(),
+        msg,
+        funds: vec![Coin {
+            denom: load_denom(deps.storage)?,
+            amount,
+        }],
+    };
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_always(msg, VESTING_REPLY_ID))
+        .add_attribute("method", "withdraw_to_vesting")
+        .add_attribute("amount", amount)
+        .add_attribute("new_balance", new_balance)
+        .add_attribute("vesting_contract", vesting_contract))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    if msg.id != VESTING_REPLY_ID {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Unknown reply id",
+        )));
+    }
+
+    let (owner, amount, dust) = PENDING_VESTING.load(deps.storage)?;
+    PENDING_VESTING.remove(deps.storage);
+
+    let error = match msg.result {
+        SubMsgResult::Err(error) => error,
+        SubMsgResult::Ok(_) => return Ok(Response::new()),
+    };
+
+    // vesting contract failed, credit the debit and any swept dust back
+    if !dust.is_zero() {
+        let dust_pool = DUST_POOL.may_load(deps.storage)?.unwrap_or_default();
+        DUST_POOL.save(deps.storage, &dust_pool.saturating_sub(dust))?;
+    }
+    let new_balance = update_balance(
         deps.storage,
-        &info.sender,
-        |balance: Option<Uint128>| -> StdResult<_> {
-            Ok(balance.unwrap_or_default().checked_sub(amount)?)
-        },
+        &USER_BALANCE,
+        &owner,
+        BalanceDelta::Add(amount + dust),
     )?;
 
-    // send uosmo to user
+    Ok(Response::new()
+        .add_attribute("method", "withdraw_to_vesting_rollback")
+        .add_attribute("owner", owner)
+        .add_attribute("new_balance", new_balance)
+        .add_attribute("error", error))
+}
+
+/// decrement `sender` balance, rejecting empty withdrawals
+fn debit(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let new_balance = update_balance(storage, &USER_BALANCE, sender, BalanceDelta::Sub(amount))?;
+
+    // sweep a sub-threshold remainder into the dust pool
+    let collect_dust = COLLECT_DUST.may_load(storage)?.unwrap_or_default();
+    let dust_threshold = DUST_THRESHOLD.may_load(storage)?.unwrap_or_default();
+    if collect_dust && !new_balance.is_zero() && new_balance < dust_threshold {
+        let dust_pool = DUST_POOL
+            .may_load(storage)?
+            .unwrap_or_default()
+            .checked_add(new_balance)
+            .map_err(StdError::from)?;
+        DUST_POOL.save(storage, &dust_pool)?;
+        USER_BALANCE.remove(storage, sender);
+        return Ok(Uint128::zero());
+    }
+
+    Ok(new_balance)
+}
+
+/// decrement `sender` balance and build the vault denom transfer to `recipient`
+fn debit_and_send(
+    deps: DepsMut,
+    sender: &Addr,
+    recipient: &Addr,
+    amount: Uint128,
+) -> Result<(CosmosMsg, Uint128), ContractError> {
+    // decrease user balance
+    let new_balance = debit(deps.storage, sender, amount)?;
+
+    // send vault denom to recipient
     let msg = CosmosMsg::Bank(BankMsg::Send {
-        to_address: info.sender.to_string(),
+        to_address: recipient.to_string(),
         amount: vec![Coin {
-            denom: "uosmo".to_string(),
+            denom: load_denom(deps.storage)?,
             amount,
         }],
     });
 
+    Ok((msg, new_balance))
+}
+
+/// contracts instantiated before the denom was configurable hold uosmo
+fn load_denom(storage: &dyn Storage) -> StdResult<String> {
+    Ok(DENOM
+        .may_load(storage)?
+        .unwrap_or_else(|| "uosmo".to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    // only migrate from this contract, legacy deployments have no version stored
+    if let Some(version) = CONTRACT.may_load(deps.storage)? {
+        if version.contract != CONTRACT_NAME {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Cannot migrate from a different contract",
+            )));
+        }
+        // never run older code over newer state
+        if parse_version(&version.version)? > parse_version(CONTRACT_VERSION)? {
+            return Err(ContractError::CannotDowngrade {
+                stored: version.version,
+                current: CONTRACT_VERSION.to_string(),
+            });
+        }
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    match msg {
+        MigrateMsg::ChangeDenom { new_denom } => {
+            validate_denom(&new_denom)?;
+
+            // balances are plain amounts, only the denom label changes
+            let old_denom = load_denom(deps.storage)?;
+            DENOM.save(deps.storage, &new_denom)?;
+
+            Ok(Response::new()
+                .add_attribute("method", "change_denom")
+                .add_attribute("old_denom", old_denom)
+                .add_attribute("new_denom", new_denom))
+        }
+    }
+}
+
+/// parse a `major.minor.patch` version, ignoring pre-release and build metadata
+fn parse_version(version: &str) -> Result<(u64, u64, u64), ContractError> {
+    let invalid = || ContractError::InvalidVersion {
+        version: version.to_string(),
+    };
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    let parts = core
+        .split('.')
+        .map(|part| part.parse::<u64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+
+    match parts[..] {
+        [major, minor, patch] => Ok((major, minor, patch)),
+        _ => Err(invalid()),
+    }
+}
+
+/// native denoms start with a letter followed by letters, digits or `/:._-`
+fn validate_denom(denom: &str) -> Result<(), ContractError> {
+    if denom.len() < MIN_DENOM_LENGTH || denom.len() > MAX_DENOM_LENGTH {
+        return Err(ContractError::InvalidDenom {});
+    }
+
+    let mut chars = denom.chars();
+    let starts_with_letter = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic());
+    if !starts_with_letter
+        || !chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | ':' | '.' | '_' | '-'))
+    {
+        return Err(ContractError::InvalidDenom {});
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::AdjustBalance { address, amount } => sudo_adjust_balance(deps, address, amount),
+    }
+}
+
+/// governance override, sets the balance without deposit validation
+fn sudo_adjust_balance(
+    deps: DepsMut,
+    address: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let address = deps.api.addr_validate(&address)?;
+    let previous = USER_BALANCE
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+    USER_BALANCE.save(deps.storage, &address, &amount)?;
+
     Ok(Response::new()
-        .add_message(msg)
-        .add_attribute("method", "withdraw")
+        .add_attribute("method", "adjust_balance")
+        .add_attribute("address", address)
+        .add_attribute("previous", previous)
         .add_attribute("amount", amount))
 }
 
@@ -99,30 +537,67 @@ pub fn try_withdraw(
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetBalance { address } => to_binary(&query_balance(deps, address)?),
+        QueryMsg::GetBalanceIn {
+            address,
+            quote_denom,
+            rate,
+        } => to_binary(&query_balance_in(deps, address, quote_denom, rate)?),
+        QueryMsg::GetAdmin {} => to_binary(&ADMIN.load(deps.storage)?),
     }
 }
 
 fn query_balance(deps: Deps, address: String) -> StdResult<BalanceResponse> {
-    let user_balance = USER_BALANCE.load(deps.storage, &deps.api.addr_validate(&address)?)?;
+    // swept and never-funded addresses have no key
+    let user_balance = USER_BALANCE
+        .may_load(deps.storage, &deps.api.addr_validate(&address)?)?
+        .unwrap_or_default();
     Ok(BalanceResponse {
         amount: Coin {
-            denom: "uosmo".to_string(),
+            denom: load_denom(deps.storage)?,
             amount: user_balance,
         },
     })
 }
 
+/// converts the user balance into `quote_denom` at the supplied rate
+fn query_balance_in(
+    deps: Deps,
+    address: String,
+    quote_denom: String,
+    rate: Decimal,
+) -> StdResult<Coin> {
+    let user_balance = query_balance(deps, address)?.amount.amount;
+    let amount = user_balance
+        .checked_multiply_ratio(rate.numerator(), rate.denominator())
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    Ok(Coin {
+        denom: quote_denom,
+        amount,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary};
+    use cosmwasm_std::{coins, from_binary, Empty, Event};
+    use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
 
     #[test]
     #[should_panic(expected = "Invalid instantiation")]
     fn invalid_init() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
         let info = mock_info("creator", &coins(0, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
     }
@@ -131,7 +606,15 @@ mod tests {
     fn deposit_success() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -153,12 +636,113 @@ mod tests {
         assert_eq!(Uint128::from(100_u64), value.amount.amount);
     }
 
+    #[test]
+    fn deposit_balance_cap() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: Some(Uint128::from(300_u64)),
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // deposits below the cap
+        let info = mock_info("alice", &coins(200, "uosmo"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        // hitting the cap exactly is allowed
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        // anything above is rejected
+        let info = mock_info("alice", &coins(1, "uosmo"));
+        let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap_err();
+        match err {
+            ContractError::CapExceeded { cap } => assert_eq!(cap, Uint128::from(300_u64)),
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetBalance {
+                address: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: BalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::from(300_u64), value.amount.amount);
+    }
+
+    #[test]
+    fn balance_in_quote_denom() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(150, "uosmo"));
+        let msg = ExecuteMsg::Deposit {};
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // balance is converted at rate 2.0
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetBalanceIn {
+                address: "alice".to_string(),
+                quote_denom: "uatom".to_string(),
+                rate: Decimal::from_ratio(2_u64, 1_u64),
+            },
+        )
+        .unwrap();
+        let value: Coin = from_binary(&res).unwrap();
+        assert_eq!(value, Coin::new(300, "uatom"));
+
+        // fractional rates round down
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetBalanceIn {
+                address: "alice".to_string(),
+                quote_denom: "uatom".to_string(),
+                rate: Decimal::percent(33),
+            },
+        )
+        .unwrap();
+        let value: Coin = from_binary(&res).unwrap();
+        assert_eq!(value, Coin::new(49, "uatom"));
+    }
+
     #[test]
     #[should_panic(expected = "Invalid deposit!")]
     fn deposit_failure() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -168,11 +752,910 @@ mod tests {
         let _err = execute(deps.as_mut(), mock_env(), info, msg);
     }
 
+    #[test]
+    fn batch_deposit_success() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // fund alice and bob in one call
+        let info = mock_info("funder", &coins(300, "uosmo"));
+        let msg = ExecuteMsg::BatchDeposit {
+            allocations: vec![
+                ("alice".to_string(), Uint128::from(100_u64)),
+                ("bob".to_string(), Uint128::from(200_u64)),
+            ],
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // verify both recipients credited
+        for (address, amount) in [("alice", 100_u64), ("bob", 200_u64)] {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetBalance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+            let value: BalanceResponse = from_binary(&res).unwrap();
+            assert_eq!(Uint128::from(amount), value.amount.amount);
+        }
+    }
+
+    #[test]
+    fn batch_deposit_failure() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // allocations must match funds
+        let info = mock_info("funder", &coins(300, "uosmo"));
+        let msg = ExecuteMsg::BatchDeposit {
+            allocations: vec![
+                ("alice".to_string(), Uint128::from(100_u64)),
+                ("bob".to_string(), Uint128::from(100_u64)),
+            ],
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::AllocationMismatch {}));
+
+        // allocation list is bounded
+        let info = mock_info("funder", &coins(51, "uosmo"));
+        let msg = ExecuteMsg::BatchDeposit {
+            allocations: vec![("alice".to_string(), Uint128::from(1_u64)); 51],
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::TooManyAllocations {}));
+    }
+
+    #[test]
+    fn withdraw_prefixed_event() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let instantiate_msg = |event_prefix: &str| InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: Some(event_prefix.to_string()),
+        };
+
+        // spaces and empty prefixes cannot form an event type
+        for event_prefix in ["", "vault 1"] {
+            let info = mock_info("creator", &coins(1000, "uosmo"));
+            let msg = instantiate_msg(event_prefix);
+            let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidEventPrefix {}));
+        }
+
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg("vault1")).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::from(40_u64),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // the withdraw event reports the remaining balance
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "vault1_withdraw");
+        assert_eq!(res.events[0].attributes, res.attributes);
+        assert!(res.events[0]
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "new_balance" && attr.value == "60"));
+    }
+
+    #[test]
+    fn withdraw_partial() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let msg = ExecuteMsg::Deposit {};
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // zero amount withdrawals are rejected
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::zero(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::ZeroAmount {}));
+
+        // remaining balance is reported
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::from(30_u64),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes[2].key, "new_balance");
+        assert_eq!(res.attributes[2].value, "70");
+    }
+
+    #[test]
+    fn migrate_change_denom() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        let msg = MigrateMsg::ChangeDenom {
+            new_denom: "uusdc".to_string(),
+        };
+        let res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(res.attributes[1].value, "uosmo");
+        assert_eq!(res.attributes[2].value, "uusdc");
+
+        // existing balances are paid out in the new denom
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::from(40_u64),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(40, "uusdc"),
+            })
+        );
+
+        // deposits follow the new denom
+        let info = mock_info("alice", &coins(10, "uusdc"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetBalance {
+                address: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: BalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(value.amount, Coin::new(70, "uusdc"));
+
+        // refuse to migrate another contract's state
+        set_contract_version(deps.as_mut().storage, "crates.io:other", "0.1.0").unwrap();
+        let msg = MigrateMsg::ChangeDenom {
+            new_denom: "uatom".to_string(),
+        };
+        let err = migrate(deps.as_mut(), mock_env(), msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Cannot migrate from a different contract"
+        );
+    }
+
+    #[test]
+    fn migrate_version_guard() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let change_denom = || MigrateMsg::ChangeDenom {
+            new_denom: "uusdc".to_string(),
+        };
+
+        // newer state cannot be migrated back
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.2.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), change_denom()).unwrap_err();
+        assert!(matches!(err, ContractError::CannotDowngrade { .. }));
+
+        // pre-release tags compare by their core version
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.1.0-beta").unwrap();
+        let _res = migrate(deps.as_mut(), mock_env(), change_denom()).unwrap();
+
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "latest").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), change_denom()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidVersion { .. }));
+
+        // older versions upgrade
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.0.9").unwrap();
+        let _res = migrate(deps.as_mut(), mock_env(), change_denom()).unwrap();
+        let version = CONTRACT.load(deps.as_ref().storage).unwrap();
+        assert_eq!(version.version, CONTRACT_VERSION);
+    }
+
+    #[test]
+    fn migrate_invalid_denom() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for new_denom in ["", "uo", "1uosmo", "u osmo", "uosmo!", &"u".repeat(129)] {
+            let msg = MigrateMsg::ChangeDenom {
+                new_denom: new_denom.to_string(),
+            };
+            let err = migrate(deps.as_mut(), mock_env(), msg).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidDenom {}));
+        }
+
+        let msg = MigrateMsg::ChangeDenom {
+            new_denom: "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+                .to_string(),
+        };
+        let _res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+    }
+
+    #[test]
+    fn withdraw_to_third_party() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let msg = ExecuteMsg::Deposit {};
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // alice pays bob from her balance
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::WithdrawTo {
+            recipient: "bob".to_string(),
+            amount: Uint128::from(60_u64),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bob".to_string(),
+                amount: coins(60, "uosmo"),
+            })
+        );
+        assert_eq!(res.attributes[2].value, "40");
+
+        // cannot pay more than the remaining balance
+        let msg = ExecuteMsg::WithdrawTo {
+            recipient: "bob".to_string(),
+            amount: Uint128::from(41_u64),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientBalance {}));
+
+        // zero amounts are rejected
+        let msg = ExecuteMsg::WithdrawTo {
+            recipient: "bob".to_string(),
+            amount: Uint128::zero(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::ZeroAmount {}));
+    }
+
+    #[test]
+    fn withdraw_to_chain_prefix() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: Some("osmo".to_string()),
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        // recipient on another chain is rejected
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::WithdrawTo {
+            recipient: "cosmos1recipient".to_string(),
+            amount: Uint128::from(60_u64),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::WrongChainPrefix {}));
+
+        // prefix alone without the separator is not enough
+        let msg = ExecuteMsg::WithdrawTo {
+            recipient: "osmosis1recipient".to_string(),
+            amount: Uint128::from(60_u64),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::WrongChainPrefix {}));
+
+        // matching prefix goes through
+        let msg = ExecuteMsg::WithdrawTo {
+            recipient: "osmo1recipient".to_string(),
+            amount: Uint128::from(60_u64),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "osmo1recipient".to_string(),
+                amount: coins(60, "uosmo"),
+            })
+        );
+        assert_eq!(res.attributes[2].value, "40");
+    }
+
+    #[test]
+    fn withdraw_sweeps_dust() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: Some(Uint128::from(10_u64)),
+            collect_dust: Some(true),
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        // remainder at the threshold is kept
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::from(90_u64),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(res.attributes[2].value, "10");
+
+        // remainder below the threshold is swept and the key removed
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::from(3_u64),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes[2].value, "0");
+        assert!(!USER_BALANCE.has(deps.as_ref().storage, &Addr::unchecked("alice")));
+        assert_eq!(
+            DUST_POOL.load(deps.as_ref().storage).unwrap(),
+            Uint128::from(7_u64)
+        );
+
+        // a swept balance still queries as zero
+        let value = query_balance(deps.as_ref(), "alice".to_string()).unwrap();
+        assert_eq!(value.amount, Coin::new(0, "uosmo"));
+
+        // admin collects the dust
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::WithdrawDust {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::WithdrawDust {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".to_string(),
+                amount: coins(7, "uosmo"),
+            })
+        );
+        assert!(DUST_POOL.load(deps.as_ref().storage).unwrap().is_zero());
+    }
+
+    #[test]
+    fn sudo_adjust_balance() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: Some(Uint128::from(300_u64)),
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        // governance overwrites the balance, ignoring the cap
+        let msg = SudoMsg::AdjustBalance {
+            address: "alice".to_string(),
+            amount: Uint128::from(500_u64),
+        };
+        let res = sudo(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(res.attributes[2].value, "100");
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetBalance {
+                address: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: BalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::from(500_u64), value.amount.amount);
+
+        // unknown addresses can be credited too
+        let msg = SudoMsg::AdjustBalance {
+            address: "bob".to_string(),
+            amount: Uint128::from(7_u64),
+        };
+        let _res = sudo(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(
+            USER_BALANCE
+                .load(deps.as_ref().storage, &Addr::unchecked("bob"))
+                .unwrap(),
+            Uint128::from(7_u64)
+        );
+    }
+
+    #[test]
+    fn withdraw_when_paused() {
+        for allow in [true, false] {
+            let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+            let msg = InstantiateMsg {
+                admin: None,
+                max_balance: None,
+                allow_withdraw_when_paused: Some(allow),
+                chain_prefix: None,
+                dust_threshold: None,
+                collect_dust: None,
+                event_prefix: None,
+            };
+            let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+            let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let info = mock_info("alice", &coins(100, "uosmo"));
+            let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+            // only the admin can pause
+            let msg = ExecuteMsg::SetPaused { paused: true };
+            let err = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("alice", &[]),
+                msg.clone(),
+            )
+            .unwrap_err();
+            assert!(matches!(err, ContractError::Unauthorized {}));
+            let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            // deposits are always blocked while paused
+            let info = mock_info("alice", &coins(100, "uosmo"));
+            let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap_err();
+            assert!(matches!(err, ContractError::Paused {}));
+
+            // withdrawals follow the configuration
+            let msg = ExecuteMsg::Withdraw {
+                amount: Uint128::from(40_u64),
+            };
+            let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg);
+            if allow {
+                assert_eq!(res.unwrap().attributes[2].value, "60");
+            } else {
+                assert!(matches!(res.unwrap_err(), ContractError::Paused {}));
+            }
+
+            // unpausing restores deposits
+            let msg = ExecuteMsg::SetPaused { paused: false };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let info = mock_info("alice", &coins(100, "uosmo"));
+            let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+    }
+
+    #[test]
+    fn instantiate_admin_override() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            admin: Some("dao".to_string()),
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
+        let info = mock_info("factory", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAdmin {}).unwrap();
+        let value: Addr = from_binary(&res).unwrap();
+        assert_eq!(value, "dao");
+
+        // the instantiating factory is not the admin
+        let msg = ExecuteMsg::SetPaused { paused: true };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("factory", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("dao", &[]), msg).unwrap();
+        assert_eq!(res.attributes[1].value, "true");
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum MockVestingMsg {
+        Vest {},
+        Reject {},
+    }
+
+    fn mock_vesting_contract() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            |_deps, _env, info: MessageInfo, msg: MockVestingMsg| -> StdResult<Response> {
+                match msg {
+                    MockVestingMsg::Vest {} => Ok(Response::new()
+                        .add_attribute("method", "vest")
+                        .add_attribute("amount", info.funds[0].amount)),
+                    MockVestingMsg::Reject {} => Err(StdError::generic_err("Vesting rejected")),
+                }
+            },
+            |_deps, _env, _info, _msg: Empty| -> StdResult<Response> { Ok(Response::new()) },
+            |_deps, _env, _msg: Empty| -> StdResult<Binary> { Ok(Binary::default()) },
+        );
+        Box::new(contract)
+    }
+
+    #[test]
+    fn withdraw_to_vesting() {
+        fn ctf_contract() -> Box<dyn Contract<Empty>> {
+            let contract = ContractWrapper::new(
+                crate::contract::execute,
+                crate::contract::instantiate,
+                crate::contract::query,
+            )
+            .with_reply(crate::contract::reply);
+            Box::new(contract)
+        }
+
+        let mut app = App::new(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked("creator"), coins(1000, "uosmo"))
+                .unwrap();
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked("alice"), coins(100, "uosmo"))
+                .unwrap();
+        });
+        let ctf_id = app.store_code(ctf_contract());
+        let vesting_id = app.store_code(mock_vesting_contract());
+
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
+        let ctf_addr = app
+            .instantiate_contract(
+                ctf_id,
+                Addr::unchecked("creator"),
+                &msg,
+                &coins(1000, "uosmo"),
+                "ctf1",
+                None,
+            )
+            .unwrap();
+        let vesting_addr = app
+            .instantiate_contract(
+                vesting_id,
+                Addr::unchecked("creator"),
+                &Empty {},
+                &[],
+                "vesting",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            ctf_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            &coins(100, "uosmo"),
+        )
+        .unwrap();
+
+        // vesting contract receives funds and message atomically
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            ctf_addr.clone(),
+            &ExecuteMsg::WithdrawToVesting {
+                amount: Uint128::from(60_u64),
+                vesting_contract: vesting_addr.to_string(),
+                msg: to_binary(&MockVestingMsg::Vest {}).unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+        let vested = app.wrap().query_balance(&vesting_addr, "uosmo").unwrap();
+        assert_eq!(vested.amount, Uint128::from(60_u64));
+
+        // the pending debit is cleared once vesting succeeds
+        let pending = app
+            .wrap()
+            .query_wasm_raw(&ctf_addr, b"pending_vesting".to_vec())
+            .unwrap();
+        assert_eq!(pending, None);
+
+        let value: BalanceResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &ctf_addr,
+                &QueryMsg::GetBalance {
+                    address: "alice".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(value.amount.amount, Uint128::from(40_u64));
+
+        // rejected vesting restores the debit
+        let res = app
+            .execute_contract(
+                Addr::unchecked("alice"),
+                ctf_addr.clone(),
+                &ExecuteMsg::WithdrawToVesting {
+                    amount: Uint128::from(40_u64),
+                    vesting_contract: vesting_addr.to_string(),
+                    msg: to_binary(&MockVestingMsg::Reject {}).unwrap(),
+                },
+                &[],
+            )
+            .unwrap();
+        assert!(res.has_event(
+            &Event::new("wasm")
+                .add_attribute("method", "withdraw_to_vesting_rollback")
+                .add_attribute("owner", "alice")
+                .add_attribute("new_balance", "40")
+        ));
+
+        let value: BalanceResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &ctf_addr,
+                &QueryMsg::GetBalance {
+                    address: "alice".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(value.amount.amount, Uint128::from(40_u64));
+        let vested = app.wrap().query_balance(&vesting_addr, "uosmo").unwrap();
+        assert_eq!(vested.amount, Uint128::from(60_u64));
+
+        // cannot vest more than the balance
+        let err = app
+            .execute_contract(
+                Addr::unchecked("alice"),
+                ctf_addr,
+                &ExecuteMsg::WithdrawToVesting {
+                    amount: Uint128::from(41_u64),
+                    vesting_contract: vesting_addr.to_string(),
+                    msg: to_binary(&MockVestingMsg::Vest {}).unwrap(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap().to_string(),
+            "Insufficient balance"
+        );
+    }
+
+    #[test]
+    fn withdraw_to_vesting_restores_dust() {
+        fn ctf_contract() -> Box<dyn Contract<Empty>> {
+            let contract = ContractWrapper::new(
+                crate::contract::execute,
+                crate::contract::instantiate,
+                crate::contract::query,
+            )
+            .with_reply(crate::contract::reply);
+            Box::new(contract)
+        }
+
+        let mut app = App::new(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked("creator"), coins(1000, "uosmo"))
+                .unwrap();
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked("alice"), coins(100, "uosmo"))
+                .unwrap();
+        });
+        let ctf_id = app.store_code(ctf_contract());
+        let vesting_id = app.store_code(mock_vesting_contract());
+
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: Some(Uint128::from(10_u64)),
+            collect_dust: Some(true),
+            event_prefix: None,
+        };
+        let ctf_addr = app
+            .instantiate_contract(
+                ctf_id,
+                Addr::unchecked("creator"),
+                &msg,
+                &coins(1000, "uosmo"),
+                "ctf1",
+                None,
+            )
+            .unwrap();
+        let vesting_addr = app
+            .instantiate_contract(
+                vesting_id,
+                Addr::unchecked("creator"),
+                &Empty {},
+                &[],
+                "vesting",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            ctf_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            &coins(100, "uosmo"),
+        )
+        .unwrap();
+
+        // the 5 uosmo remainder is swept, then the vesting contract rejects
+        let res = app
+            .execute_contract(
+                Addr::unchecked("alice"),
+                ctf_addr.clone(),
+                &ExecuteMsg::WithdrawToVesting {
+                    amount: Uint128::from(95_u64),
+                    vesting_contract: vesting_addr.to_string(),
+                    msg: to_binary(&MockVestingMsg::Reject {}).unwrap(),
+                },
+                &[],
+            )
+            .unwrap();
+        assert!(res.has_event(
+            &Event::new("wasm")
+                .add_attribute("method", "withdraw_to_vesting_rollback")
+                .add_attribute("owner", "alice")
+                .add_attribute("new_balance", "100")
+        ));
+
+        // the dust goes back to alice, not the admin
+        let value: BalanceResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &ctf_addr,
+                &QueryMsg::GetBalance {
+                    address: "alice".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(value.amount.amount, Uint128::from(100_u64));
+        let err = app
+            .execute_contract(
+                Addr::unchecked("creator"),
+                ctf_addr,
+                &ExecuteMsg::WithdrawDust {},
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::ZeroAmount {}
+        ));
+    }
+
     #[test]
     fn exploit() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            admin: None,
+            max_balance: None,
+            allow_withdraw_when_paused: None,
+            chain_prefix: None,
+            dust_threshold: None,
+            collect_dust: None,
+            event_prefix: None,
+        };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,6 +9,42 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Allocations do not match deposited funds")]
+    AllocationMismatch {},
+
+    #[error("Too many allocations")]
+    TooManyAllocations {},
+
+    #[error("Amount must be greater than zero")]
+    ZeroAmount {},
+
+    #[error("Balance cap of {cap} exceeded")]
+    CapExceeded { cap: Uint128 },
+
+    #[error("Insufficient balance")]
+    InsufficientBalance {},
+
+    #[error("Balance overflow")]
+    BalanceOverflow {},
+
+    #[error("Contract is paused")]
+    Paused {},
+
+    #[error("Recipient prefix does not match this chain")]
+    WrongChainPrefix {},
+
+    #[error("Invalid denom")]
+    InvalidDenom {},
+
+    #[error("Invalid contract version {version}")]
+    InvalidVersion { version: String },
+
+    #[error("Cannot migrate from {stored} to older version {current}")]
+    CannotDowngrade { stored: String, current: String },
+
+    #[error("Invalid event prefix")]
+    InvalidEventPrefix {},
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
     // Add any other custom errors you like here.
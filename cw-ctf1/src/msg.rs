@@ -1,19 +1,65 @@
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Binary, Decimal, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    pub admin: Option<String>,
+    pub max_balance: Option<Uint128>,
+    pub allow_withdraw_when_paused: Option<bool>,
+    pub chain_prefix: Option<String>,
+    pub dust_threshold: Option<Uint128>,
+    pub collect_dust: Option<bool>,
+    pub event_prefix: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     Deposit {},
-    Withdraw { amount: Uint128 },
+    Withdraw {
+        amount: Uint128,
+    },
+    WithdrawTo {
+        recipient: String,
+        amount: Uint128,
+    },
+    BatchDeposit {
+        allocations: Vec<(String, Uint128)>,
+    },
+    WithdrawToVesting {
+        amount: Uint128,
+        vesting_contract: String,
+        msg: Binary,
+    },
+    SetPaused {
+        paused: bool,
+    },
+    WithdrawDust {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrateMsg {
+    ChangeDenom { new_denom: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SudoMsg {
+    AdjustBalance { address: String, amount: Uint128 },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    GetBalance { address: String },
+    GetBalance {
+        address: String,
+    },
+    GetBalanceIn {
+        address: String,
+        quote_denom: String,
+        rate: Decimal,
+    },
+    GetAdmin {},
 }
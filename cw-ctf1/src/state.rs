@@ -1,4 +1,37 @@
 use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::Map;
+use cw_storage_plus::{Item, Map};
 
 pub const USER_BALANCE: Map<&Addr, Uint128> = Map::new("user_balance");
+
+/// owner, debit and swept dust to restore if the vesting submessage fails
+pub const PENDING_VESTING: Item<(Addr, Uint128, Uint128)> = Item::new("pending_vesting");
+
+/// vault denom, uosmo when unset
+pub const DENOM: Item<String> = Item::new("denom");
+
+/// store admin address
+pub const ADMIN: Item<Addr> = Item::new("admin_addr");
+
+/// deposits are blocked while paused
+pub const PAUSED: Item<bool> = Item::new("paused");
+
+/// whether users can still withdraw while paused
+pub const ALLOW_WITHDRAW_WHEN_PAUSED: Item<bool> = Item::new("allow_withdraw_when_paused");
+
+/// per-address balance cap, zero means unlimited
+pub const MAX_BALANCE: Item<Uint128> = Item::new("max_balance");
+
+/// bech32 prefix required on withdrawal recipients, empty when not configured
+pub const CHAIN_PREFIX: Item<String> = Item::new("chain_prefix");
+
+/// remainders below this amount are swept when dust collection is enabled
+pub const DUST_THRESHOLD: Item<Uint128> = Item::new("dust_threshold");
+
+/// whether sub-threshold remainders are swept into the dust pool
+pub const COLLECT_DUST: Item<bool> = Item::new("collect_dust");
+
+/// swept dust, withdrawable by the admin
+pub const DUST_POOL: Item<Uint128> = Item::new("dust_pool");
+
+/// prefix for custom event types, empty when not configured
+pub const EVENT_PREFIX: Item<String> = Item::new("event_prefix");
@@ -1,20 +1,30 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, BalanceResponse, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
-    Response, StdError, StdResult, Uint128,
+    to_binary, Addr, BalanceResponse, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env,
+    MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128,
 };
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::USER_BALANCE;
+use crate::helpers::{add_prefixed_event, update_balance, validate_event_prefix, BalanceDelta};
+use crate::msg::{
+    AllowanceResponse, BalancesResponse, ContractInfoResponse, ExecuteMsg, InstantiateMsg,
+    QueryMsg, SolvencyResponse,
+};
+use crate::state::{
+    ADMIN, ALLOWANCES, EVENT_PREFIX, FROZEN, LIFETIME_DEPOSITED, TREASURY, USER_BALANCE,
+    WITHDRAW_FEE_BPS,
+};
+
+/// default and maximum number of addresses in a single balances page
+const MAX_BALANCE_ADDRESSES: usize = 50;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     // admin must provide 1000 uosmo when instantiating contract
     if info.funds.len() != 1
@@ -26,6 +36,32 @@ pub fn instantiate(
         )));
     }
 
+    // withdrawal fee defaults to zero
+    let withdraw_fee_bps = msg.withdraw_fee_bps.unwrap_or_default();
+    if withdraw_fee_bps > 10_000 {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Invalid withdraw fee",
+        )));
+    }
+
+    // we set ourself as admin
+    ADMIN.save(deps.storage, &info.sender)?;
+
+    // treasury defaults to the instantiator
+    let treasury = match msg.treasury {
+        Some(treasury) => deps.api.addr_validate(&treasury)?,
+        None => info.sender,
+    };
+
+    WITHDRAW_FEE_BPS.save(deps.storage, &withdraw_fee_bps)?;
+    TREASURY.save(deps.storage, &treasury)?;
+
+    // optional prefix for custom events
+    if let Some(event_prefix) = &msg.event_prefix {
+        validate_event_prefix(event_prefix)?;
+    }
+    EVENT_PREFIX.save(deps.storage, &msg.event_prefix.unwrap_or_default())?;
+
     Ok(Response::new())
 }
 
@@ -36,10 +72,35 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    match msg {
+    let event_prefix = EVENT_PREFIX.may_load(deps.storage)?.unwrap_or_default();
+
+    let res = match msg {
         ExecuteMsg::Deposit {} => try_deposit(deps, info),
         ExecuteMsg::Withdraw { amount } => try_withdraw(deps, info, amount),
+        ExecuteMsg::IncreaseAllowance { spender, amount } => {
+            try_increase_allowance(deps, info, spender, amount)
+        }
+        ExecuteMsg::DecreaseAllowance { spender, amount } => {
+            try_decrease_allowance(deps, info, spender, amount)
+        }
+        ExecuteMsg::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        } => try_transfer_from(deps, info, owner, recipient, amount),
+        ExecuteMsg::SetFrozen { address, frozen } => try_set_frozen(deps, info, address, frozen),
+        ExecuteMsg::ResetBalance { address } => try_reset_balance(deps, info, address),
+    }?;
+
+    Ok(add_prefixed_event(res, &event_prefix))
+}
+
+/// reject frozen accounts moving funds out
+fn check_not_frozen(storage: &dyn Storage, address: &Addr) -> Result<(), ContractError> {
+    if FROZEN.may_load(storage, address)?.unwrap_or_default() {
+        return Err(ContractError::AccountFrozen {});
     }
+    Ok(())
 }
 
 pub fn try_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
@@ -51,11 +112,21 @@ pub fn try_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, Contrac
     }
 
     // update user balance
-    USER_BALANCE.update(
+    update_balance(
         deps.storage,
+        &USER_BALANCE,
         &info.sender,
-        |balance: Option<u128>| -> StdResult<_> {
-            Ok(balance.unwrap_or_default() + info.funds[0].amount.u128())
+        BalanceDelta::Add(info.funds[0].amount),
+    )?;
+
+    // lifetime deposits only ever increase
+    LIFETIME_DEPOSITED.update(
+        deps.storage,
+        &info.sender,
+        |deposited: Option<Uint128>| -> StdResult<_> {
+            Ok(deposited
+                .unwrap_or_default()
+                .checked_add(info.funds[0].amount)?)
         },
     )?;
 
@@ -69,6 +140,8 @@ pub fn try_withdraw(
     info: MessageInfo,
     amount: u128,
 ) -> Result<Response, ContractError> {
+    check_not_frozen(deps.storage, &info.sender)?;
+
     // decrease user balance
     USER_BALANCE.update(
         deps.storage,
@@ -76,25 +149,204 @@ pub fn try_withdraw(
         |balance: Option<u128>| -> StdResult<_> { Ok(balance.unwrap_or_default() - amount) },
     )?;
 
+    // compute withdrawal fee
+    let withdraw_fee_bps = WITHDRAW_FEE_BPS.load(deps.storage)?;
+    let fee = Uint128::from(amount)
+        .checked_multiply_ratio(withdraw_fee_bps, 10_000_u64)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    let user_amount = Uint128::from(amount)
+        .checked_sub(fee)
+        .map_err(StdError::from)?;
+
     // send uosmo to user
-    let msg = CosmosMsg::Bank(BankMsg::Send {
+    let mut msgs = vec![CosmosMsg::Bank(BankMsg::Send {
         to_address: info.sender.to_string(),
         amount: vec![Coin {
             denom: "uosmo".to_string(),
-            amount: Uint128::from(amount),
+            amount: user_amount,
         }],
-    });
+    })];
+
+    // send fee to treasury
+    if !fee.is_zero() {
+        msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: TREASURY.load(deps.storage)?.to_string(),
+            amount: vec![Coin {
+                denom: "uosmo".to_string(),
+                amount: fee,
+            }],
+        }));
+    }
 
     Ok(Response::new()
-        .add_message(msg)
+        .add_messages(msgs)
         .add_attribute("method", "withdraw")
-        .add_attribute("amount", amount.to_string()))
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("fee", fee))
+}
+
+pub fn try_increase_allowance(
+    deps: DepsMut,
+    info: MessageInfo,
+    spender: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let spender = deps.api.addr_validate(&spender)?;
+    if spender == info.sender {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot set allowance to own account",
+        )));
+    }
+
+    let allowance = ALLOWANCES.update(
+        deps.storage,
+        (&info.sender, &spender),
+        |allowance: Option<Uint128>| -> StdResult<_> {
+            Ok(allowance.unwrap_or_default().checked_add(amount)?)
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("method", "increase_allowance")
+        .add_attribute("owner", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("allowance", allowance))
+}
+
+pub fn try_decrease_allowance(
+    deps: DepsMut,
+    info: MessageInfo,
+    spender: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let spender = deps.api.addr_validate(&spender)?;
+    let key = (&info.sender, &spender);
+
+    // decreasing below zero removes the allowance
+    let allowance = ALLOWANCES
+        .may_load(deps.storage, key)?
+        .unwrap_or_default()
+        .saturating_sub(amount);
+    if allowance.is_zero() {
+        ALLOWANCES.remove(deps.storage, key);
+    } else {
+        ALLOWANCES.save(deps.storage, key, &allowance)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "decrease_allowance")
+        .add_attribute("owner", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("allowance", allowance))
+}
+
+pub fn try_transfer_from(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: String,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    check_not_frozen(deps.storage, &owner)?;
+    check_not_frozen(deps.storage, &info.sender)?;
+
+    // spend allowance
+    let allowance = ALLOWANCES
+        .may_load(deps.storage, (&owner, &info.sender))?
+        .unwrap_or_default()
+        .checked_sub(amount)
+        .map_err(|_| ContractError::InsufficientAllowance {})?;
+
+    // move balance from owner to recipient
+    update_balance(
+        deps.storage,
+        &USER_BALANCE,
+        &owner,
+        BalanceDelta::Sub(amount),
+    )?;
+    ALLOWANCES.save(deps.storage, (&owner, &info.sender), &allowance)?;
+    update_balance(
+        deps.storage,
+        &USER_BALANCE,
+        &recipient,
+        BalanceDelta::Add(amount),
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("method", "transfer_from")
+        .add_attribute("owner", owner)
+        .add_attribute("recipient", recipient)
+        .add_attribute("spender", info.sender)
+        .add_attribute("amount", amount))
+}
+
+pub fn try_set_frozen(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    frozen: bool,
+) -> Result<Response, ContractError> {
+    // verify sender is admin
+    if info.sender != ADMIN.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let address = deps.api.addr_validate(&address)?;
+    if frozen {
+        FROZEN.save(deps.storage, &address, &true)?;
+    } else {
+        FROZEN.remove(deps.storage, &address);
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "set_frozen")
+        .add_attribute("address", address)
+        .add_attribute("frozen", frozen.to_string()))
+}
+
+/// clear a user balance, meant for staging deployments
+pub fn try_reset_balance(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    // verify sender is admin
+    if info.sender != ADMIN.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let address = deps.api.addr_validate(&address)?;
+    let removed = USER_BALANCE
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+    USER_BALANCE.remove(deps.storage, &address);
+
+    Ok(Response::new()
+        .add_attribute("method", "reset_balance")
+        .add_attribute("address", address)
+        .add_attribute("removed", removed.to_string()))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetBalance { address } => to_binary(&query_balance(deps, address)?),
+        QueryMsg::GetLifetimeDeposited { address } => {
+            to_binary(&query_lifetime_deposited(deps, address)?)
+        }
+        QueryMsg::GetSolvency {} => to_binary(&query_solvency(deps, env)?),
+        QueryMsg::GetAllowance { owner, spender } => {
+            to_binary(&query_allowance(deps, owner, spender)?)
+        }
+        QueryMsg::GetBalances {
+            addresses,
+            start_after,
+            limit,
+        } => to_binary(&query_balances(deps, addresses, start_after, limit)?),
+        QueryMsg::IsFrozen { address } => to_binary(&query_is_frozen(deps, address)?),
+        QueryMsg::GetContractInfo {} => to_binary(&query_contract_info(deps)?),
     }
 }
 
@@ -110,17 +362,126 @@ fn query_balance(deps: Deps, address: String) -> StdResult<BalanceResponse> {
     })
 }
 
+/// balances for a page of the given addresses, unknown addresses report zero,
+/// `start_after` is the index of the last address already returned
+fn query_balances(
+    deps: Deps,
+    addresses: Vec<String>,
+    start_after: Option<u32>,
+    limit: Option<u32>,
+) -> StdResult<BalancesResponse> {
+    let limit = limit
+        .map_or(MAX_BALANCE_ADDRESSES, |l| l as usize)
+        .clamp(1, MAX_BALANCE_ADDRESSES);
+    let start = start_after.map_or(0, |i| i as usize + 1);
+    let end = start.saturating_add(limit).min(addresses.len());
+
+    let balances = addresses
+        .iter()
+        .skip(start)
+        .take(limit)
+        .map(|address| {
+            let balance = USER_BALANCE
+                .may_load(deps.storage, &deps.api.addr_validate(address)?)?
+                .unwrap_or_default();
+            Ok((address.clone(), Uint128::from(balance)))
+        })
+        .collect::<StdResult<Vec<(String, Uint128)>>>()?;
+
+    // more addresses remain after this page
+    let next_index = if end < addresses.len() {
+        Some(end as u32 - 1)
+    } else {
+        None
+    };
+
+    Ok(BalancesResponse {
+        balances,
+        next_index,
+    })
+}
+
+fn query_is_frozen(deps: Deps, address: String) -> StdResult<bool> {
+    Ok(FROZEN
+        .may_load(deps.storage, &deps.api.addr_validate(&address)?)?
+        .unwrap_or_default())
+}
+
+fn query_lifetime_deposited(deps: Deps, address: String) -> StdResult<BalanceResponse> {
+    let deposited = LIFETIME_DEPOSITED
+        .may_load(deps.storage, &deps.api.addr_validate(&address)?)?
+        .unwrap_or_default();
+    Ok(BalanceResponse {
+        amount: Coin {
+            denom: "uosmo".to_string(),
+            amount: deposited,
+        },
+    })
+}
+
+fn query_allowance(deps: Deps, owner: String, spender: String) -> StdResult<AllowanceResponse> {
+    let allowance = ALLOWANCES
+        .may_load(
+            deps.storage,
+            (
+                &deps.api.addr_validate(&owner)?,
+                &deps.api.addr_validate(&spender)?,
+            ),
+        )?
+        .unwrap_or_default();
+    Ok(AllowanceResponse { allowance })
+}
+
+/// sum of all user balances, saturating so an overflowed ledger stays visible
+fn total_user_balances(deps: Deps) -> StdResult<u128> {
+    USER_BALANCE
+        .range(deps.storage, None, None, Order::Ascending)
+        .try_fold(0_u128, |acc, v| -> StdResult<_> {
+            Ok(acc.saturating_add(v?.1))
+        })
+}
+
+/// contract metadata in a single call, supply is the sum of user balances
+fn query_contract_info(deps: Deps) -> StdResult<ContractInfoResponse> {
+    Ok(ContractInfoResponse {
+        denom: "uosmo".to_string(),
+        admin: ADMIN.load(deps.storage)?.to_string(),
+        total_supply: Uint128::from(total_user_balances(deps)?),
+    })
+}
+
+/// compare the sum of all user balances against the contract's uosmo holdings
+fn query_solvency(deps: Deps, env: Env) -> StdResult<SolvencyResponse> {
+    // saturate so an overflowed ledger still reports as insolvent
+    let total_balances = total_user_balances(deps)?;
+    let contract_balance = deps
+        .querier
+        .query_balance(env.contract.address, "uosmo")?
+        .amount;
+
+    Ok(SolvencyResponse {
+        total_balances: Uint128::from(total_balances),
+        contract_balance,
+        solvent: Uint128::from(total_balances) <= contract_balance,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::helpers::MAX_EVENT_PREFIX_LENGTH;
     use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary};
+    use cosmwasm_std::{coins, from_binary, Addr};
 
     #[test]
     #[should_panic(expected = "Invalid instantiation")]
     fn invalid_init() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: None,
+        };
         let info = mock_info("creator", &coins(0, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
     }
@@ -129,7 +490,11 @@ mod tests {
     fn deposit_success() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: None,
+        };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -156,7 +521,11 @@ mod tests {
     fn deposit_failure() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: None,
+        };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -171,7 +540,11 @@ mod tests {
     fn exploit_fail() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: None,
+        };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -191,12 +564,547 @@ mod tests {
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     }
 
+    #[test]
+    fn lifetime_deposited() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // alice deposits twice
+        for amount in [100_u128, 50] {
+            let info = mock_info("alice", &coins(amount, "uosmo"));
+            let msg = ExecuteMsg::Deposit {};
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        // withdrawals do not reduce the lifetime figure
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw { amount: 120_u128 };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetLifetimeDeposited {
+                address: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: BalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::from(150_u64), value.amount.amount);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetBalance {
+                address: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: BalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::from(30_u64), value.amount.amount);
+    }
+
+    #[test]
+    fn solvency() {
+        let mut deps = mock_dependencies_with_balance(&coins(1100, "uosmo"));
+
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let msg = ExecuteMsg::Deposit {};
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetSolvency {}).unwrap();
+        let value: SolvencyResponse = from_binary(&res).unwrap();
+        assert_eq!(value.total_balances, Uint128::from(100_u64));
+        assert_eq!(value.contract_balance, Uint128::from(1100_u64));
+        assert!(value.solvent);
+
+        // seed the balance an underflowing withdrawal would leave behind
+        USER_BALANCE
+            .save(
+                deps.as_mut().storage,
+                &Addr::unchecked("hacker"),
+                &(0_u128.wrapping_sub(1000)),
+            )
+            .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetSolvency {}).unwrap();
+        let value: SolvencyResponse = from_binary(&res).unwrap();
+        assert!(value.total_balances > value.contract_balance);
+        assert!(!value.solvent);
+    }
+
+    #[test]
+    fn contract_info() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for (sender, amount) in [("alice", 100_u128), ("bob", 50)] {
+            let info = mock_info(sender, &coins(amount, "uosmo"));
+            let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetContractInfo {}).unwrap();
+        let value: ContractInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value,
+            ContractInfoResponse {
+                denom: "uosmo".to_string(),
+                admin: "creator".to_string(),
+                total_supply: Uint128::from(150_u64),
+            }
+        );
+    }
+
+    #[test]
+    fn withdraw_fee_split() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: Some(100),
+            treasury: Some("treasury".to_string()),
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(1000, "uosmo"));
+        let msg = ExecuteMsg::Deposit {};
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // 1% fee goes to the treasury
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw { amount: 1000_u128 };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(990, "uosmo"),
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(10, "uosmo"),
+            })
+        );
+    }
+
+    #[test]
+    fn withdraw_no_fee() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(1000, "uosmo"));
+        let msg = ExecuteMsg::Deposit {};
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // full amount sent to user in a single message
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw { amount: 1000_u128 };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(1000, "uosmo"),
+            })
+        );
+    }
+
+    #[test]
+    fn transfer_from_allowance() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        // alice grants the router an allowance
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: "router".to_string(),
+            amount: Uint128::from(80_u64),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::DecreaseAllowance {
+            spender: "router".to_string(),
+            amount: Uint128::from(20_u64),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let query_allowance = |deps: Deps| -> Uint128 {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::GetAllowance {
+                    owner: "alice".to_string(),
+                    spender: "router".to_string(),
+                },
+            )
+            .unwrap();
+            let value: AllowanceResponse = from_binary(&res).unwrap();
+            value.allowance
+        };
+        assert_eq!(query_allowance(deps.as_ref()), Uint128::from(60_u64));
+
+        // spending within the allowance
+        let info = mock_info("router", &[]);
+        let msg = ExecuteMsg::TransferFrom {
+            owner: "alice".to_string(),
+            recipient: "bob".to_string(),
+            amount: Uint128::from(50_u64),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(query_allowance(deps.as_ref()), Uint128::from(10_u64));
+
+        let query_balance = |deps: Deps, address: &str| -> Uint128 {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::GetBalance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+            let value: BalanceResponse = from_binary(&res).unwrap();
+            value.amount.amount
+        };
+        assert_eq!(query_balance(deps.as_ref(), "alice"), Uint128::from(50_u64));
+        assert_eq!(query_balance(deps.as_ref(), "bob"), Uint128::from(50_u64));
+
+        // exceeding the allowance
+        let info = mock_info("router", &[]);
+        let msg = ExecuteMsg::TransferFrom {
+            owner: "alice".to_string(),
+            recipient: "bob".to_string(),
+            amount: Uint128::from(11_u64),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientAllowance {}));
+        assert_eq!(query_balance(deps.as_ref(), "alice"), Uint128::from(50_u64));
+    }
+
+    #[test]
+    fn transfer_from_prefixed_event() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        // prefixes longer than the limit are rejected
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: Some("b".repeat(MAX_EVENT_PREFIX_LENGTH + 1)),
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo"));
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidEventPrefix {}));
+
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: Some("bank2".to_string()),
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: "bob".to_string(),
+            amount: Uint128::from(30_u64),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::TransferFrom {
+            owner: "alice".to_string(),
+            recipient: "carol".to_string(),
+            amount: Uint128::from(30_u64),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), msg).unwrap();
+
+        // the spender shows up in the transfer event
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "bank2_transfer_from");
+        assert_eq!(res.events[0].attributes, res.attributes);
+        assert!(res.events[0]
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "spender" && attr.value == "bob"));
+    }
+
+    #[test]
+    fn batch_balances() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for (address, amount) in [("alice", 100_u128), ("bob", 250)] {
+            let info = mock_info(address, &coins(amount, "uosmo"));
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+
+        // carol never deposited and reports zero
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetBalances {
+                addresses: vec!["alice".to_string(), "bob".to_string(), "carol".to_string()],
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: BalancesResponse = from_binary(&res).unwrap();
+        assert_eq!(value.next_index, None);
+        assert_eq!(
+            value.balances,
+            vec![
+                ("alice".to_string(), Uint128::from(100_u64)),
+                ("bob".to_string(), Uint128::from(250_u64)),
+                ("carol".to_string(), Uint128::zero()),
+            ]
+        );
+
+        // page size is capped
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetBalances {
+                addresses: vec!["alice".to_string(); 51],
+                start_after: None,
+                limit: Some(100),
+            },
+        )
+        .unwrap();
+        let value: BalancesResponse = from_binary(&res).unwrap();
+        assert_eq!(value.balances.len(), 50);
+        assert_eq!(value.next_index, Some(49));
+    }
+
+    #[test]
+    fn get_balances_paging() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let addresses: Vec<String> = (0..120).map(|i| format!("user{:03}", i)).collect();
+        for (i, address) in addresses.iter().enumerate().step_by(10) {
+            let info = mock_info(address, &coins(i as u128 + 1, "uosmo"));
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+
+        // three pages of 50, 50 and 20
+        let mut balances = vec![];
+        let mut start_after = None;
+        let mut pages = 0;
+        loop {
+            let msg = QueryMsg::GetBalances {
+                addresses: addresses.clone(),
+                start_after,
+                limit: None,
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            let value: BalancesResponse = from_binary(&res).unwrap();
+            balances.extend(value.balances);
+            pages += 1;
+            match value.next_index {
+                Some(next_index) => start_after = Some(next_index),
+                None => break,
+            }
+        }
+        assert_eq!(pages, 3);
+        assert_eq!(balances.len(), 120);
+        assert_eq!(balances[0], ("user000".to_string(), Uint128::from(1_u64)));
+        assert_eq!(balances[1], ("user001".to_string(), Uint128::zero()));
+        assert_eq!(
+            balances[110],
+            ("user110".to_string(), Uint128::from(111_u64))
+        );
+    }
+
+    #[test]
+    fn freeze_account() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        // only the admin can freeze
+        let msg = ExecuteMsg::SetFrozen {
+            address: "alice".to_string(),
+            frozen: true,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let is_frozen = |deps: Deps| -> bool {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::IsFrozen {
+                    address: "alice".to_string(),
+                },
+            )
+            .unwrap();
+            from_binary(&res).unwrap()
+        };
+        assert!(is_frozen(deps.as_ref()));
+
+        // withdrawals and transfers are blocked, deposits are not
+        let msg = ExecuteMsg::Withdraw { amount: 10 };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::AccountFrozen {}));
+
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: "bob".to_string(),
+            amount: Uint128::from(50_u64),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        let msg = ExecuteMsg::TransferFrom {
+            owner: "alice".to_string(),
+            recipient: "bob".to_string(),
+            amount: Uint128::from(10_u64),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::AccountFrozen {}));
+
+        let info = mock_info("alice", &coins(50, "uosmo"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        // unfreezing restores withdrawals
+        let msg = ExecuteMsg::SetFrozen {
+            address: "alice".to_string(),
+            frozen: false,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert!(!is_frozen(deps.as_ref()));
+
+        let msg = ExecuteMsg::Withdraw { amount: 10 };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn reset_balance() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        // only the admin can reset
+        let msg = ExecuteMsg::ResetBalance {
+            address: "alice".to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.attributes[2].value, "100");
+        assert!(!USER_BALANCE.has(deps.as_ref().storage, &Addr::unchecked("alice")));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetBalance {
+                address: "alice".to_string(),
+            },
+        )
+        .unwrap();
+        let value: BalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(value.amount.amount, Uint128::zero());
+    }
+
     #[test]
     fn exploit() {
         // hint: use `cargo test --release` instead of `cargo test`
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            withdraw_fee_bps: None,
+            treasury: None,
+            event_prefix: None,
+        };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
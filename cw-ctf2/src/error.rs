@@ -9,6 +9,21 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Insufficient allowance")]
+    InsufficientAllowance {},
+
+    #[error("Insufficient balance")]
+    InsufficientBalance {},
+
+    #[error("Balance overflow")]
+    BalanceOverflow {},
+
+    #[error("Account frozen")]
+    AccountFrozen {},
+
+    #[error("Invalid event prefix")]
+    InvalidEventPrefix {},
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
     // Add any other custom errors you like here.
@@ -2,11 +2,13 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{
-    to_binary, Addr, BalanceResponse, CosmosMsg, CustomQuery, Querier, QuerierWrapper, StdResult,
-    WasmMsg, WasmQuery,
+    to_binary, Addr, BalanceResponse, CosmosMsg, CustomQuery, Event, Querier, QuerierWrapper,
+    Response, StdResult, Storage, Uint128, WasmMsg, WasmQuery,
 };
+use cw_storage_plus::Map;
 
 use crate::msg::{ExecuteMsg, QueryMsg};
+use crate::ContractError;
 
 // CwTemplateContract is a wrapper around Addr that provides a lot of helpers
 // for working with this.
@@ -45,3 +47,120 @@ impl CwTemplateContract {
         Ok(res)
     }
 }
+
+/// signed change applied to a ledger balance
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BalanceDelta {
+    Add(Uint128),
+    Sub(Uint128),
+}
+
+/// apply `delta` to the raw u128 balance stored under `addr` and return the new balance
+pub fn update_balance(
+    storage: &mut dyn Storage,
+    map: &Map<&Addr, u128>,
+    addr: &Addr,
+    delta: BalanceDelta,
+) -> Result<Uint128, ContractError> {
+    let balance = map.may_load(storage, addr)?.unwrap_or_default();
+    let new_balance = match delta {
+        BalanceDelta::Add(amount) => balance
+            .checked_add(amount.u128())
+            .ok_or(ContractError::BalanceOverflow {})?,
+        BalanceDelta::Sub(amount) => balance
+            .checked_sub(amount.u128())
+            .ok_or(ContractError::InsufficientBalance {})?,
+    };
+    map.save(storage, addr, &new_balance)?;
+    Ok(Uint128::from(new_balance))
+}
+
+/// longest accepted event prefix
+pub const MAX_EVENT_PREFIX_LENGTH: usize = 32;
+
+/// event prefixes must be non-empty ascii letters, digits, `_` or `-`
+pub fn validate_event_prefix(event_prefix: &str) -> Result<(), ContractError> {
+    let valid = !event_prefix.is_empty()
+        && event_prefix.len() <= MAX_EVENT_PREFIX_LENGTH
+        && event_prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(ContractError::InvalidEventPrefix {});
+    }
+    Ok(())
+}
+
+/// tag ledger actions with a `<prefix>_<method>` event carrying the response attributes, e.g. `bank2_transfer_from`
+pub fn add_prefixed_event(res: Response, event_prefix: &str) -> Response {
+    if event_prefix.is_empty() {
+        return res;
+    }
+
+    let method = res
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "method")
+        .map_or("", |attr| attr.value.as_str());
+    let event =
+        Event::new(format!("{}_{}", event_prefix, method)).add_attributes(res.attributes.clone());
+
+    res.add_event(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+
+    const BALANCES: Map<&Addr, u128> = Map::new("balances");
+
+    #[test]
+    fn update_balance_add() {
+        let mut storage = MockStorage::new();
+        let alice = Addr::unchecked("alice");
+
+        let balance = update_balance(
+            &mut storage,
+            &BALANCES,
+            &alice,
+            BalanceDelta::Add(Uint128::from(100_u64)),
+        )
+        .unwrap();
+        assert_eq!(balance, Uint128::from(100_u64));
+        assert_eq!(BALANCES.load(&storage, &alice).unwrap(), 100);
+    }
+
+    #[test]
+    fn update_balance_sub() {
+        let mut storage = MockStorage::new();
+        let alice = Addr::unchecked("alice");
+        BALANCES.save(&mut storage, &alice, &100).unwrap();
+
+        let balance = update_balance(
+            &mut storage,
+            &BALANCES,
+            &alice,
+            BalanceDelta::Sub(Uint128::from(40_u64)),
+        )
+        .unwrap();
+        assert_eq!(balance, Uint128::from(60_u64));
+    }
+
+    #[test]
+    fn update_balance_underflow() {
+        let mut storage = MockStorage::new();
+        let alice = Addr::unchecked("alice");
+        BALANCES.save(&mut storage, &alice, &100).unwrap();
+
+        let err = update_balance(
+            &mut storage,
+            &BALANCES,
+            &alice,
+            BalanceDelta::Sub(Uint128::from(101_u64)),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientBalance {}));
+        assert_eq!(BALANCES.load(&storage, &alice).unwrap(), 100);
+    }
+}
@@ -1,18 +1,94 @@
+use cosmwasm_std::Uint128;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    pub withdraw_fee_bps: Option<u64>,
+    pub treasury: Option<String>,
+    pub event_prefix: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     Deposit {},
-    Withdraw { amount: u128 },
+    Withdraw {
+        amount: u128,
+    },
+    IncreaseAllowance {
+        spender: String,
+        amount: Uint128,
+    },
+    DecreaseAllowance {
+        spender: String,
+        amount: Uint128,
+    },
+    TransferFrom {
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    SetFrozen {
+        address: String,
+        frozen: bool,
+    },
+    ResetBalance {
+        address: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    GetBalance { address: String },
+    GetBalance {
+        address: String,
+    },
+    GetLifetimeDeposited {
+        address: String,
+    },
+    GetSolvency {},
+    GetAllowance {
+        owner: String,
+        spender: String,
+    },
+    GetBalances {
+        addresses: Vec<String>,
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
+    IsFrozen {
+        address: String,
+    },
+    GetContractInfo {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AllowanceResponse {
+    pub allowance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SolvencyResponse {
+    pub total_balances: Uint128,
+    pub contract_balance: Uint128,
+    pub solvent: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ContractInfoResponse {
+    pub denom: String,
+    pub admin: String,
+    pub total_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct BalancesResponse {
+    pub balances: Vec<(String, Uint128)>,
+    /// pass as `start_after` to fetch the next page, none once every address is covered
+    pub next_index: Option<u32>,
 }
@@ -1,4 +1,18 @@
-use cosmwasm_std::Addr;
-use cw_storage_plus::Map;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
 
 pub const USER_BALANCE: Map<&Addr, u128> = Map::new("user_balance");
+pub const LIFETIME_DEPOSITED: Map<&Addr, Uint128> = Map::new("lifetime_deposited");
+/// (owner, spender) to amount the spender may transfer from the owner
+pub const ALLOWANCES: Map<(&Addr, &Addr), Uint128> = Map::new("allowances");
+pub const WITHDRAW_FEE_BPS: Item<u64> = Item::new("withdraw_fee_bps");
+pub const TREASURY: Item<Addr> = Item::new("treasury");
+
+/// store admin address
+pub const ADMIN: Item<Addr> = Item::new("admin_addr");
+
+/// accounts blocked from moving funds out
+pub const FROZEN: Map<&Addr, bool> = Map::new("frozen");
+
+/// prefix for custom event types, empty when not configured
+pub const EVENT_PREFIX: Item<String> = Item::new("event_prefix");
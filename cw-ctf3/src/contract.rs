@@ -6,15 +6,16 @@ use cosmwasm_std::{
 };
 
 use crate::error::ContractError;
+use crate::helpers::{add_prefixed_event, validate_event_prefix};
 use crate::msg::{DebtResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{USER_BALANCE, USER_BORROW};
+use crate::state::{EVENT_PREFIX, USER_BALANCE, USER_BORROW};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     // admin must provide 1000 uosmo when instantiating contract
     if info.funds.len() != 1
@@ -26,6 +27,12 @@ pub fn instantiate(
         )));
     }
 
+    // optional prefix for custom events
+    if let Some(event_prefix) = &msg.event_prefix {
+        validate_event_prefix(event_prefix)?;
+    }
+    EVENT_PREFIX.save(deps.storage, &msg.event_prefix.unwrap_or_default())?;
+
     Ok(Response::new())
 }
 
@@ -36,12 +43,16 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    match msg {
+    let event_prefix = EVENT_PREFIX.may_load(deps.storage)?.unwrap_or_default();
+
+    let res = match msg {
         ExecuteMsg::Deposit {} => try_deposit(deps, info),
         ExecuteMsg::Withdraw { amount } => try_withdraw(deps, info, amount),
         ExecuteMsg::Borrow { amount } => try_borrow(deps, info, amount),
         ExecuteMsg::Repay {} => try_repay(deps, info),
-    }
+    }?;
+
+    Ok(add_prefixed_event(res, &event_prefix))
 }
 
 pub fn try_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
@@ -220,7 +231,7 @@ mod tests {
     #[should_panic(expected = "Invalid instantiation")]
     fn invalid_init() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg { event_prefix: None };
         let info = mock_info("creator", &coins(0, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
     }
@@ -229,7 +240,7 @@ mod tests {
     fn deposit_success() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg { event_prefix: None };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -256,7 +267,7 @@ mod tests {
     fn deposit_failure() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg { event_prefix: None };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -270,7 +281,7 @@ mod tests {
     fn borrow_success() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg { event_prefix: None };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -293,7 +304,7 @@ mod tests {
     fn borrow_fail() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg { event_prefix: None };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -311,11 +322,44 @@ mod tests {
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     }
 
+    #[test]
+    fn borrow_prefixed_event() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        // dots are not allowed in event types
+        let msg = InstantiateMsg {
+            event_prefix: Some("lend.3".to_string()),
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo"));
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidEventPrefix {}));
+
+        let msg = InstantiateMsg {
+            event_prefix: Some("lend3".to_string()),
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Borrow {
+            amount: Uint128::from(50_u64),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // borrow event carries the borrowed amount
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "lend3_borrow");
+        assert_eq!(res.events[0].attributes, res.attributes);
+    }
+
     #[test]
     fn exploit() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg { event_prefix: None };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -2,11 +2,12 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{
-    to_binary, Addr, BalanceResponse, CosmosMsg, CustomQuery, Querier, QuerierWrapper, StdResult,
-    WasmMsg, WasmQuery,
+    to_binary, Addr, BalanceResponse, CosmosMsg, CustomQuery, Event, Querier, QuerierWrapper,
+    Response, StdResult, WasmMsg, WasmQuery,
 };
 
 use crate::msg::{ExecuteMsg, QueryMsg};
+use crate::ContractError;
 
 // CwTemplateContract is a wrapper around Addr that provides a lot of helpers
 // for working with this.
@@ -45,3 +46,36 @@ impl CwTemplateContract {
         Ok(res)
     }
 }
+
+/// longest accepted event prefix
+pub const MAX_EVENT_PREFIX_LENGTH: usize = 32;
+
+/// event prefixes must be non-empty ascii letters, digits, `_` or `-`
+pub fn validate_event_prefix(event_prefix: &str) -> Result<(), ContractError> {
+    let valid = !event_prefix.is_empty()
+        && event_prefix.len() <= MAX_EVENT_PREFIX_LENGTH
+        && event_prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(ContractError::InvalidEventPrefix {});
+    }
+    Ok(())
+}
+
+/// emit a `<prefix>_<method>` event for lending actions, e.g. `lend3_borrow`
+pub fn add_prefixed_event(res: Response, event_prefix: &str) -> Response {
+    if event_prefix.is_empty() {
+        return res;
+    }
+
+    let method = res
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "method")
+        .map_or("", |attr| attr.value.as_str());
+    let event =
+        Event::new(format!("{}_{}", event_prefix, method)).add_attributes(res.attributes.clone());
+
+    res.add_event(event)
+}
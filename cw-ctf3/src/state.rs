@@ -1,5 +1,8 @@
 use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::Map;
+use cw_storage_plus::{Item, Map};
 
 pub const USER_BALANCE: Map<&Addr, Uint128> = Map::new("user_balance");
 pub const USER_BORROW: Map<&Addr, Uint128> = Map::new("user_borrow");
+
+/// prefix for custom event types, empty when not configured
+pub const EVENT_PREFIX: Item<String> = Item::new("event_prefix");
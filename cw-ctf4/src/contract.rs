@@ -3,18 +3,32 @@ use std::ops::Mul;
 use std::str::FromStr;
 
 use crate::error::ContractError;
+use crate::helpers::{add_prefixed_event, update_balance, validate_event_prefix, BalanceDelta};
 use crate::msg::{
-    AnchorQueryMsg, EpochStateResponse, ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg,
+    AggregateInterestResponse, AnchorQueryMsg, DepositHistoryResponse, EpochStateResponse,
+    ExecuteMsg, InstantiateMsg, QueryMsg, RateHistoryResponse, ReceiveMsg, SimulateRedeemResponse,
+};
+use crate::state::{
+    DepositRecord, AUST_ADDRESS, DEPOSIT_HISTORY, DEPOSIT_HISTORY_COUNT, EVENT_PREFIX, FAIL_CLOSED,
+    MAX_RATE_AGE, OUTPUT_DENOM, POOL_BALANCE, RATE_HISTORY, USER_BALANCE, USER_CREDITED_UST,
+    USER_RAW_AUST, WRAPPER_TOKEN,
 };
-use crate::state::{AUST_ADDRESS, USER_BALANCE};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_slice, to_binary, BalanceResponse, BankMsg, Binary, Coin, CosmosMsg, Decimal256, Deps,
-    DepsMut, Env, MessageInfo, QueryRequest, Response, StdError, StdResult, Uint128, Uint256,
-    WasmQuery,
+    from_slice, to_binary, Addr, BalanceResponse, BankMsg, Binary, Coin, CosmosMsg, Decimal256,
+    Deps, DepsMut, Env, Fraction, MessageInfo, Order, QueryRequest, Response, StdError, StdResult,
+    Storage, Uint128, Uint256, WasmMsg, WasmQuery,
 };
-use cw20::Cw20ReceiveMsg;
+use cw20::{Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg, TokenInfoResponse};
+use cw_storage_plus::Bound;
+
+/// default and maximum page size for paginated queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// maximum number of entries in a single batch deposit
+const MAX_BATCH_DEPOSITS: u32 = 50;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -33,11 +47,73 @@ pub fn instantiate(
         )));
     }
 
+    // reject missing addresses before validation for a clearer error
+    if msg.aust_address.is_empty() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "aust_address required",
+        )));
+    }
     let aust_address = deps.api.addr_validate(&msg.aust_address)?;
 
+    // optionally catch a misconfigured token by querying its cw20 info
+    if msg.verify_cw20.unwrap_or_default() {
+        deps.querier
+            .query_wasm_smart::<TokenInfoResponse>(&aust_address, &Cw20QueryMsg::TokenInfo {})
+            .map_err(|_| ContractError::NotACw20 {})?;
+    }
+
+    // output denom follows the sdk denom format
+    if !is_valid_denom(&msg.output_denom) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Invalid output denom",
+        )));
+    }
+
     AUST_ADDRESS.save(deps.storage, &aust_address)?;
+    OUTPUT_DENOM.save(deps.storage, &msg.output_denom)?;
+    if let Some(max_rate_age) = msg.max_rate_age {
+        MAX_RATE_AGE.save(deps.storage, &max_rate_age)?;
+    }
+
+    // deposits fail when the anchor rate is unavailable unless disabled
+    FAIL_CLOSED.save(deps.storage, &msg.fail_closed.unwrap_or(true))?;
+
+    // only this token may redeem through the cw20 hook
+    if let Some(wrapper_token) = msg.wrapper_token {
+        WRAPPER_TOKEN.save(deps.storage, &deps.api.addr_validate(&wrapper_token)?)?;
+    }
 
-    Ok(Response::new())
+    // optional prefix for custom events
+    if let Some(event_prefix) = &msg.event_prefix {
+        validate_event_prefix(event_prefix)?;
+    }
+    EVENT_PREFIX.save(deps.storage, &msg.event_prefix.unwrap_or_default())?;
+
+    Ok(Response::new().add_attribute("aust_address", aust_address))
+}
+
+/// sdk denoms are 3 to 128 characters, starting with a letter
+fn is_valid_denom(denom: &str) -> bool {
+    (3..=128).contains(&denom.len())
+        && denom.starts_with(|c: char| c.is_ascii_alphabetic())
+        && denom
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c))
+}
+
+/// reject rates observed too many blocks away from the current height
+fn check_rate_age(
+    storage: &dyn Storage,
+    rate_height: u64,
+    current_height: u64,
+) -> Result<(), ContractError> {
+    if let Some(max_rate_age) = MAX_RATE_AGE.may_load(storage)? {
+        if current_height.abs_diff(rate_height) > max_rate_age {
+            return Err(ContractError::StaleRate {});
+        }
+    }
+
+    Ok(())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -47,30 +123,41 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    match msg {
+    let event_prefix = EVENT_PREFIX.may_load(deps.storage)?.unwrap_or_default();
+
+    let res = match msg {
         ExecuteMsg::Deposit {} => try_deposit(deps, info),
         ExecuteMsg::Withdraw { amount } => try_withdraw(deps, info, amount),
         ExecuteMsg::Receive(wrapper) => handle_receive(deps, env, info, wrapper),
-    }
+        ExecuteMsg::Redeem { amount, recipient } => {
+            try_redeem(deps, env, info, 0, amount, recipient)
+        }
+        ExecuteMsg::RedeemFromPool {
+            pool_id,
+            amount,
+            recipient,
+        } => try_redeem(deps, env, info, pool_id, amount, recipient),
+    }?;
+
+    Ok(add_prefixed_event(res, &event_prefix))
 }
 
 pub fn try_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
-    // validate uosmo sent
-    if info.funds.len() != 1 || info.funds[0].denom != "uosmo" {
+    let output_denom = OUTPUT_DENOM.load(deps.storage)?;
+
+    // validate output denom sent
+    if info.funds.len() != 1 || info.funds[0].denom != output_denom {
         return Err(ContractError::Std(StdError::generic_err(
             "Invalid deposit!",
         )));
     }
 
     // update user balance
-    USER_BALANCE.update(
+    update_balance(
         deps.storage,
+        &USER_BALANCE,
         &info.sender,
-        |balance: Option<Uint128>| -> StdResult<_> {
-            Ok(balance
-                .unwrap_or_default()
-                .checked_add(info.funds[0].amount)?)
-        },
+        BalanceDelta::Add(info.funds[0].amount),
     )?;
 
     Ok(Response::new()
@@ -84,19 +171,18 @@ pub fn try_withdraw(
     amount: Uint128,
 ) -> Result<Response, ContractError> {
     // decrease user balance
-    USER_BALANCE.update(
+    update_balance(
         deps.storage,
+        &USER_BALANCE,
         &info.sender,
-        |balance: Option<Uint128>| -> StdResult<_> {
-            Ok(balance.unwrap_or_default().checked_sub(amount)?)
-        },
+        BalanceDelta::Sub(amount),
     )?;
 
-    // send uosmo to user
+    // send output denom to user
     let msg = CosmosMsg::Bank(BankMsg::Send {
         to_address: info.sender.to_string(),
         amount: vec![Coin {
-            denom: "uosmo".to_string(),
+            denom: OUTPUT_DENOM.load(deps.storage)?,
             amount,
         }],
     });
@@ -107,95 +193,530 @@ pub fn try_withdraw(
         .add_attribute("amount", amount))
 }
 
+pub fn try_redeem(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_id: u64,
+    amount: Uint128,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    // aUST goes to the caller unless another recipient is given
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender.clone(),
+    };
+
+    let res = redeem_aust(deps, env, &info.sender, pool_id, amount, recipient)?;
+    Ok(res.add_attribute("pool_id", pool_id.to_string()))
+}
+
+/// debit `owner` in `pool_id` the output denom value of `amount` aUST and send the aUST to `recipient`
+fn redeem_aust(
+    deps: DepsMut,
+    env: Env,
+    owner: &Addr,
+    pool_id: u64,
+    amount: Uint128,
+    recipient: Addr,
+) -> Result<Response, ContractError> {
+    // load storage aust address
+    let aust_address = AUST_ADDRESS.load(deps.storage)?;
+
+    // value the redeemed aUST in the output denom
+    let epoch_state = query_epoch_state(deps.as_ref(), &aust_address, env.block.height)?;
+    if epoch_state.exchange_rate == Decimal256::zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Invalid exchange rate",
+        )));
+    }
+    let redeemed_amount = Uint128::try_from(Uint256::from(amount).mul(epoch_state.exchange_rate))
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    // decrease owner balance, pool 0 is the flat user balance
+    if pool_id == 0 {
+        update_balance(
+            deps.storage,
+            &USER_BALANCE,
+            owner,
+            BalanceDelta::Sub(redeemed_amount),
+        )?;
+    } else {
+        let balance = POOL_BALANCE
+            .may_load(deps.storage, (pool_id, owner))?
+            .unwrap_or_default()
+            .checked_sub(redeemed_amount)
+            .map_err(|_| ContractError::InsufficientBalance {})?;
+        POOL_BALANCE.save(deps.storage, (pool_id, owner), &balance)?;
+    }
+
+    // transfer aUST to recipient
+    let msg = WasmMsg::Execute {
+        contract_addr: aust_address.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    };
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("method", "redeem")
+        .add_attribute("amount", amount)
+        .add_attribute("redeemed_amount", redeemed_amount)
+        .add_attribute("recipient", recipient))
+}
+
+/// query anchor epoch state at the given height
+fn query_epoch_state(
+    deps: Deps,
+    aust_address: &Addr,
+    block_height: u64,
+) -> StdResult<EpochStateResponse> {
+    deps.querier
+        .query::<EpochStateResponse>(&QueryRequest::Wasm(WasmQuery::Smart {
+            // anchor money market address
+            contract_addr: aust_address.to_string(),
+            msg: to_binary(&AnchorQueryMsg::EpochState {
+                block_height: Some(block_height),
+                distributed_interest: None,
+            })?,
+        }))
+}
+
 pub fn handle_receive(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     wrapper: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
     let msg: ReceiveMsg = from_slice(&wrapper.msg)?;
-    let total_amount;
-    let exchange_rate;
     match msg {
-        ReceiveMsg::Deposit {} => {
-            // get sender and amount received
-            let sender = deps.api.addr_validate(&wrapper.sender)?;
-            let amount = wrapper.amount;
-
-            // load storage aust address
-            let aust_address = AUST_ADDRESS.load(deps.storage)?;
-
-            // calculate exchange rate for aUST to OSMO
-            let epoch_state = deps
-                .querier
-                .query::<EpochStateResponse>(&QueryRequest::Wasm(WasmQuery::Smart {
-                    // anchor money market address
-                    contract_addr: aust_address.to_string(),
-                    msg: to_binary(&AnchorQueryMsg::EpochState {
-                        block_height: Some(env.block.height),
-                        distributed_interest: None,
-                    })?,
-                }))?;
-
-            // prevent edge cases
-            if epoch_state.exchange_rate == Decimal256::zero() {
-                return Err(ContractError::Std(StdError::generic_err(
-                    "Invalid exchange rate",
-                )));
-            }
-            exchange_rate = epoch_state.exchange_rate;
-
-            let calculated_amount =
-                Uint128::try_from(Uint256::from(amount).mul(epoch_state.exchange_rate))
-                    .expect("Unable to convert Uint256 into Uint128");
-            total_amount = calculated_amount;
-
-            // update user balance
-            USER_BALANCE.update(
-                deps.storage,
-                &sender,
-                |balance: Option<Uint128>| -> StdResult<_> {
-                    Ok(balance.unwrap_or_default().checked_add(calculated_amount)?)
-                },
-            )?;
+        ReceiveMsg::Deposit { min_ust_out } => try_deposit_aust(deps, env, wrapper, 0, min_ust_out),
+        ReceiveMsg::DepositToPool { pool_id } => {
+            try_deposit_aust(deps, env, wrapper, pool_id, None)
         }
+        ReceiveMsg::DepositBatch { deposits } => {
+            try_deposit_batch(deps, env, info, wrapper, deposits)
+        }
+        // wrapper tokens are burned and credit nothing, the redemption debits the sender ledger
+        ReceiveMsg::Redeem {} => try_redeem_receive(deps, env, info, wrapper),
     }
+}
 
-    Ok(Response::new()
+fn try_redeem_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // the hook sender is only trusted when it is the wrapper token
+    if WRAPPER_TOKEN.may_load(deps.storage)? != Some(info.sender.clone()) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let sender = deps.api.addr_validate(&wrapper.sender)?;
+
+    // burn the received wrapper tokens
+    let burn = WasmMsg::Execute {
+        contract_addr: info.sender.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Burn {
+            amount: wrapper.amount,
+        })?,
+        funds: vec![],
+    };
+
+    let res = redeem_aust(deps, env, &sender, 0, wrapper.amount, sender.clone())?;
+    Ok(res
+        .add_message(burn)
+        .add_attribute("burned", wrapper.amount))
+}
+
+fn try_deposit_aust(
+    mut deps: DepsMut,
+    env: Env,
+    wrapper: Cw20ReceiveMsg,
+    pool_id: u64,
+    min_ust_out: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    // get sender and amount received
+    let sender = deps.api.addr_validate(&wrapper.sender)?;
+    let amount = wrapper.amount;
+
+    // calculate exchange rate for aUST to output denom
+    let (exchange_rate, fallback) = deposit_rate(deps.branch(), &env)?;
+
+    let calculated_amount = credit_aust(
+        deps.storage,
+        &sender,
+        amount,
+        exchange_rate,
+        pool_id,
+        env.block.height,
+        min_ust_out,
+    )?;
+
+    let mut res = Response::new()
         .add_attribute("method", "deposit")
         .add_attribute("sent_amount", wrapper.amount)
         .add_attribute("exchange_rate", exchange_rate.to_string())
-        .add_attribute("total_amount", total_amount))
+        .add_attribute("total_amount", calculated_amount)
+        .add_attribute("pool_id", pool_id.to_string());
+    if fallback {
+        res = res.add_attribute("rate_source", "fallback");
+    }
+
+    Ok(res)
+}
+
+/// exchange rate applied to deposits, and whether it came from the fallback
+fn deposit_rate(deps: DepsMut, env: &Env) -> Result<(Decimal256, bool), ContractError> {
+    // load storage aust address
+    let aust_address = AUST_ADDRESS.load(deps.storage)?;
+
+    // a fresh rate is observed at the current height
+    let (exchange_rate, fallback) =
+        match query_epoch_state(deps.as_ref(), &aust_address, env.block.height) {
+            Ok(epoch_state) => {
+                // remember the rate observed at this height for audits
+                RATE_HISTORY.save(deps.storage, env.block.height, &epoch_state.exchange_rate)?;
+                (epoch_state.exchange_rate, false)
+            }
+            Err(err) => (fallback_rate(deps.storage, err, env.block.height)?, true),
+        };
+
+    // prevent edge cases
+    if exchange_rate == Decimal256::zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Invalid exchange rate",
+        )));
+    }
+
+    Ok((exchange_rate, fallback))
+}
+
+/// last recorded exchange rate, used when the anchor query fails and the contract is not fail closed
+fn fallback_rate(
+    storage: &dyn Storage,
+    err: StdError,
+    block_height: u64,
+) -> Result<Decimal256, ContractError> {
+    if FAIL_CLOSED.may_load(storage)?.unwrap_or(true) {
+        return Err(err.into());
+    }
+
+    match RATE_HISTORY
+        .range(storage, None, None, Order::Descending)
+        .next()
+    {
+        Some(item) => {
+            let (rate_height, exchange_rate) = item?;
+            // the last recorded rate ages like any other
+            check_rate_age(storage, rate_height, block_height)?;
+            Ok(exchange_rate)
+        }
+        None => Err(err.into()),
+    }
+}
+
+/// splits one received aUST transfer across several users, querying the rate once
+fn try_deposit_batch(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+    deposits: Vec<(String, Uint128)>,
+) -> Result<Response, ContractError> {
+    // only the aUST token can forward batches
+    if info.sender != AUST_ADDRESS.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if deposits.len() > MAX_BATCH_DEPOSITS as usize {
+        return Err(ContractError::BatchTooLarge {
+            max: MAX_BATCH_DEPOSITS,
+        });
+    }
+
+    // every credited entry must be backed by the received tokens
+    let batch_amount = deposits
+        .iter()
+        .try_fold(Uint128::zero(), |acc, (_, amount)| acc.checked_add(*amount))
+        .map_err(StdError::from)?;
+    if batch_amount != wrapper.amount {
+        return Err(ContractError::BatchAmountMismatch {});
+    }
+
+    // calculate exchange rate for aUST to output denom
+    let (exchange_rate, fallback) = deposit_rate(deps.branch(), &env)?;
+
+    let mut total_amount = Uint128::zero();
+    for (sender, amount) in &deposits {
+        let sender = deps.api.addr_validate(sender)?;
+        let calculated_amount = credit_aust(
+            deps.storage,
+            &sender,
+            *amount,
+            exchange_rate,
+            0,
+            env.block.height,
+            None,
+        )?;
+        total_amount = total_amount
+            .checked_add(calculated_amount)
+            .map_err(StdError::from)?;
+    }
+
+    let mut res = Response::new()
+        .add_attribute("method", "deposit_batch")
+        .add_attribute("exchange_rate", exchange_rate.to_string())
+        .add_attribute("deposits", deposits.len().to_string())
+        .add_attribute("total_amount", total_amount);
+    if fallback {
+        res = res.add_attribute("rate_source", "fallback");
+    }
+
+    Ok(res)
+}
+
+/// convert aUST at `exchange_rate` and credit the sender's balance in `pool_id`
+fn credit_aust(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    amount: Uint128,
+    exchange_rate: Decimal256,
+    pool_id: u64,
+    block_height: u64,
+    min_credit: Option<Uint128>,
+) -> Result<Uint128, ContractError> {
+    let calculated_amount = Uint128::try_from(Uint256::from(amount).mul(exchange_rate))
+        .expect("Unable to convert Uint256 into Uint128");
+
+    // tiny deposits could truncate to zero credit
+    if calculated_amount.is_zero() {
+        return Err(ContractError::CreditTooSmall {});
+    }
+
+    // the rate may have dropped since the depositor signed
+    if let Some(min) = min_credit {
+        if calculated_amount < min {
+            return Err(ContractError::SlippageExceeded {
+                got: calculated_amount,
+                min,
+            });
+        }
+    }
+
+    // update user balance, pool 0 is the flat user balance
+    if pool_id == 0 {
+        update_balance(
+            storage,
+            &USER_BALANCE,
+            sender,
+            BalanceDelta::Add(calculated_amount),
+        )?;
+    } else {
+        POOL_BALANCE.update(
+            storage,
+            (pool_id, sender),
+            |balance: Option<Uint128>| -> StdResult<_> {
+                Ok(balance.unwrap_or_default().checked_add(calculated_amount)?)
+            },
+        )?;
+    }
+
+    // track raw aUST and credited amount for analytics
+    USER_RAW_AUST.update(
+        storage,
+        sender,
+        |balance: Option<Uint128>| -> StdResult<_> {
+            Ok(balance.unwrap_or_default().checked_add(amount)?)
+        },
+    )?;
+    USER_CREDITED_UST.update(
+        storage,
+        sender,
+        |balance: Option<Uint128>| -> StdResult<_> {
+            Ok(balance.unwrap_or_default().checked_add(calculated_amount)?)
+        },
+    )?;
+
+    // append to the user's deposit history
+    let id = DEPOSIT_HISTORY_COUNT
+        .may_load(storage, sender)?
+        .unwrap_or_default();
+    DEPOSIT_HISTORY.save(
+        storage,
+        (sender, id),
+        &DepositRecord {
+            id,
+            aust_amount: amount,
+            ust_credited: calculated_amount,
+            exchange_rate,
+            block_height,
+        },
+    )?;
+    DEPOSIT_HISTORY_COUNT.save(storage, sender, &(id + 1))?;
+
+    Ok(calculated_amount)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetBalance { address } => to_binary(&query_balance(deps, address)?),
         QueryMsg::GetAnchorRate {
             block_height,
             distributed_interest,
-        } => to_binary(&query_aust_rate(deps, block_height, distributed_interest)?),
+        } => to_binary(&query_aust_rate(
+            deps,
+            env,
+            block_height,
+            distributed_interest,
+        )?),
+        QueryMsg::GetAggregateInterest {} => to_binary(&query_aggregate_interest(deps)?),
+        QueryMsg::GetPoolBalance { pool_id, address } => {
+            to_binary(&query_pool_balance(deps, pool_id, address)?)
+        }
+        QueryMsg::SimulateRedeem { ust_amount } => {
+            to_binary(&query_simulate_redeem(deps, env, ust_amount)?)
+        }
+        QueryMsg::GetDepositHistory {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&query_deposit_history(deps, address, start_after, limit)?),
+        QueryMsg::WillOverflow {
+            aust_amount,
+            block_height,
+        } => to_binary(&query_will_overflow(deps, env, aust_amount, block_height)?),
+        QueryMsg::GetExchangeRateHistory { start_after, limit } => {
+            to_binary(&query_rate_history(deps, start_after, limit)?)
+        }
     }
 }
 
 fn query_balance(deps: Deps, address: String) -> StdResult<BalanceResponse> {
-    let user_balance = USER_BALANCE.load(deps.storage, &deps.api.addr_validate(&address)?)?;
+    // addresses that never deposited hold zero
+    let user_balance = USER_BALANCE
+        .may_load(deps.storage, &deps.api.addr_validate(&address)?)?
+        .unwrap_or_default();
     Ok(BalanceResponse {
         amount: Coin {
-            denom: "uosmo".to_string(),
+            denom: OUTPUT_DENOM.load(deps.storage)?,
             amount: Uint128::from_str(&user_balance.to_string())?,
         },
     })
 }
 
+fn query_pool_balance(deps: Deps, pool_id: u64, address: String) -> StdResult<BalanceResponse> {
+    // pool 0 is the flat user balance
+    if pool_id == 0 {
+        return query_balance(deps, address);
+    }
+
+    let pool_balance = POOL_BALANCE
+        .may_load(deps.storage, (pool_id, &deps.api.addr_validate(&address)?))?
+        .unwrap_or_default();
+    Ok(BalanceResponse {
+        amount: Coin {
+            denom: OUTPUT_DENOM.load(deps.storage)?,
+            amount: pool_balance,
+        },
+    })
+}
+
+/// whether crediting `aust_amount` at the anchor rate would overflow `Uint128`
+fn query_will_overflow(
+    deps: Deps,
+    env: Env,
+    aust_amount: Uint128,
+    block_height: Option<u64>,
+) -> StdResult<bool> {
+    let aust_address = AUST_ADDRESS.load(deps.storage)?;
+    let block_height = block_height.unwrap_or(env.block.height);
+    let epoch_state = query_epoch_state(deps, &aust_address, block_height)?;
+
+    let calculated_amount = Uint256::from(aust_amount).mul(epoch_state.exchange_rate);
+    Ok(Uint128::try_from(calculated_amount).is_err())
+}
+
+/// paginate a user's aUST deposits, oldest first
+fn query_deposit_history(
+    deps: Deps,
+    address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<DepositHistoryResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let deposits = DEPOSIT_HISTORY
+        .prefix(&address)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|v| Ok(v?.1))
+        .collect::<StdResult<Vec<DepositRecord>>>()?;
+
+    Ok(DepositHistoryResponse { deposits })
+}
+
+/// paginate fresh anchor rates observed by deposits, keyed by block height
+fn query_rate_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<RateHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let rates = RATE_HISTORY
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(u64, Decimal256)>>>()?;
+
+    Ok(RateHistoryResponse { rates })
+}
+
+/// aUST needed to redeem `ust_amount` at the current rate, the inverse of the deposit math
+fn query_simulate_redeem(
+    deps: Deps,
+    env: Env,
+    ust_amount: Uint128,
+) -> StdResult<SimulateRedeemResponse> {
+    let aust_address = AUST_ADDRESS.load(deps.storage)?;
+    let epoch_state = query_epoch_state(deps, &aust_address, env.block.height)?;
+    let exchange_rate = epoch_state.exchange_rate;
+    if exchange_rate == Decimal256::zero() {
+        return Err(StdError::generic_err("Invalid exchange rate"));
+    }
+
+    let aust_amount = Uint256::from(ust_amount)
+        .checked_mul(exchange_rate.denominator())?
+        .checked_div(exchange_rate.numerator())?;
+
+    Ok(SimulateRedeemResponse {
+        aust_amount: Uint128::try_from(aust_amount)?,
+        exchange_rate,
+    })
+}
+
 fn query_aust_rate(
     deps: Deps,
+    env: Env,
     block_height: Option<u64>,
     distributed_interest: Option<Uint256>,
 ) -> StdResult<EpochStateResponse> {
     let aust_address = AUST_ADDRESS.load(deps.storage)?;
 
+    // default to the current block to avoid stale rates
+    let block_height = block_height.unwrap_or(env.block.height);
+    // guard against rates from a halted chain
+    check_rate_age(deps.storage, block_height, env.block.height)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    let block_height = Some(block_height);
+
     let epoch_state = deps
         .querier
         .query::<EpochStateResponse>(&QueryRequest::Wasm(WasmQuery::Smart {
@@ -209,6 +730,26 @@ fn query_aust_rate(
     Ok(epoch_state)
 }
 
+/// sum raw aUST deposited and amount credited for it across all users
+fn query_aggregate_interest(deps: Deps) -> StdResult<AggregateInterestResponse> {
+    let total_raw_aust = USER_RAW_AUST
+        .range(deps.storage, None, None, Order::Ascending)
+        .try_fold(Uint128::zero(), |acc, v| -> StdResult<_> {
+            Ok(acc.checked_add(v?.1)?)
+        })?;
+    let total_credited_ust = USER_CREDITED_UST
+        .range(deps.storage, None, None, Order::Ascending)
+        .try_fold(Uint128::zero(), |acc, v| -> StdResult<_> {
+            Ok(acc.checked_add(v?.1)?)
+        })?;
+
+    Ok(AggregateInterestResponse {
+        total_raw_aust,
+        total_credited_ust,
+        total_interest: total_credited_ust.saturating_sub(total_raw_aust),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::borrow::BorrowMut;
@@ -216,8 +757,11 @@ mod tests {
     use crate::mock_anchor;
 
     use super::*;
-    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
-    use cosmwasm_std::{coin, coins, from_binary, Addr, Decimal256, Empty};
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info, MockQuerier};
+    use cosmwasm_std::{
+        coin, coins, from_binary, Addr, ContractResult, Decimal256, Empty, Event, OwnedDeps,
+        SystemResult,
+    };
     use cw_multi_test::{App, BankSudo, Contract, ContractWrapper, Executor, SudoMsg};
     use mock_anchor::InstantiateMsg as AnchorInstantiateMsg;
 
@@ -227,17 +771,69 @@ mod tests {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
         let msg = InstantiateMsg {
             aust_address: "terra1hzh9vpxhsk8253se0vv5jj6etdvxu3nv8z07zu".to_string(),
+            output_denom: "uosmo".to_string(),
+            max_rate_age: None,
+            verify_cw20: None,
+            fail_closed: None,
+            wrapper_token: None,
+            event_prefix: None,
         };
         let info = mock_info("creator", &coins(0, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
     }
 
+    #[test]
+    fn init_aust_address() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        // empty address is rejected up front
+        let msg = InstantiateMsg {
+            aust_address: "".to_string(),
+            output_denom: "uosmo".to_string(),
+            max_rate_age: None,
+            verify_cw20: None,
+            fail_closed: None,
+            wrapper_token: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err.to_string(), "Generic error: aust_address required");
+
+        // valid address is stored and reported
+        let msg = InstantiateMsg {
+            aust_address: "terra1hzh9vpxhsk8253se0vv5jj6etdvxu3nv8z07zu".to_string(),
+            output_denom: "uosmo".to_string(),
+            max_rate_age: None,
+            verify_cw20: None,
+            fail_closed: None,
+            wrapper_token: None,
+            event_prefix: None,
+        };
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes[0].key, "aust_address");
+        assert_eq!(
+            res.attributes[0].value,
+            "terra1hzh9vpxhsk8253se0vv5jj6etdvxu3nv8z07zu"
+        );
+        assert_eq!(
+            AUST_ADDRESS.load(deps.as_ref().storage).unwrap(),
+            "terra1hzh9vpxhsk8253se0vv5jj6etdvxu3nv8z07zu"
+        );
+    }
+
     #[test]
     fn deposit_success() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
         let msg = InstantiateMsg {
             aust_address: "terra1hzh9vpxhsk8253se0vv5jj6etdvxu3nv8z07zu".to_string(),
+            output_denom: "uosmo".to_string(),
+            max_rate_age: None,
+            verify_cw20: None,
+            fail_closed: None,
+            wrapper_token: None,
+            event_prefix: None,
         };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -260,6 +856,35 @@ mod tests {
         assert_eq!(Uint128::from(100_u64), value.amount.amount);
     }
 
+    #[test]
+    fn query_balance_unknown_address() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            aust_address: "terra1hzh9vpxhsk8253se0vv5jj6etdvxu3nv8z07zu".to_string(),
+            output_denom: "uosmo".to_string(),
+            max_rate_age: None,
+            verify_cw20: None,
+            fail_closed: None,
+            wrapper_token: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // never deposited, balance is zero instead of an error
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetBalance {
+                address: "bob".to_string(),
+            },
+        )
+        .unwrap();
+        let value: BalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(value.amount, Coin::new(0, "uosmo"));
+    }
+
     #[test]
     #[should_panic(expected = "Invalid deposit!")]
     fn deposit_failure() {
@@ -267,6 +892,12 @@ mod tests {
 
         let msg = InstantiateMsg {
             aust_address: "terra1hzh9vpxhsk8253se0vv5jj6etdvxu3nv8z07zu".to_string(),
+            output_denom: "uosmo".to_string(),
+            max_rate_age: None,
+            verify_cw20: None,
+            fail_closed: None,
+            wrapper_token: None,
+            event_prefix: None,
         };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -277,34 +908,491 @@ mod tests {
         let _err = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     }
 
-    /// helper function to setup aust and ctf contract and return the addresses
-    fn setup_contracts(app: &mut App) -> (Addr, Addr) {
-        // create mock anchor contract box
-        fn aust_contract() -> Box<dyn Contract<Empty>> {
-            let contract = ContractWrapper::new(
-                mock_anchor::execute,
-                mock_anchor::instantiate,
-                mock_anchor::query,
-            );
-            Box::new(contract)
-        }
+    #[test]
+    fn redeem_to_recipient() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        // create ctf contract box
-        fn ctf_contract() -> Box<dyn Contract<Empty>> {
-            let contract = ContractWrapper::new(
-                crate::contract::execute,
-                crate::contract::instantiate,
-                crate::contract::query,
-            );
-            Box::new(contract)
-        }
+        // anchor reports a 1.20 exchange rate
+        deps.querier.update_wasm(|_| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&EpochStateResponse {
+                    exchange_rate: Decimal256::from_str("1.20").unwrap(),
+                    aterra_supply: Uint256::zero(),
+                })
+                .unwrap(),
+            ))
+        });
+
+        let msg = InstantiateMsg {
+            aust_address: "aust".to_string(),
+            output_denom: "uosmo".to_string(),
+            max_rate_age: None,
+            verify_cw20: None,
+            fail_closed: None,
+            wrapper_token: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info(ALICE, &coins(1_200, "uosmo"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        // redeem 100 aUST to a cold wallet
+        let info = mock_info(ALICE, &[]);
+        let msg = ExecuteMsg::Redeem {
+            amount: Uint128::from(100_u64),
+            recipient: Some("cold".to_string()),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "aust".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "cold".to_string(),
+                    amount: Uint128::from(100_u64),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        // caller ledger is debited at the exchange rate
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetBalance {
+                address: ALICE.to_string(),
+            },
+        )
+        .unwrap();
+        let value: BalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(value.amount.amount, Uint128::from(1_080_u64));
+    }
+
+    #[test]
+    fn redeem_from_pool() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        // anchor reports a 1.20 exchange rate
+        deps.querier.update_wasm(|_| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&EpochStateResponse {
+                    exchange_rate: Decimal256::from_str("1.20").unwrap(),
+                    aterra_supply: Uint256::zero(),
+                })
+                .unwrap(),
+            ))
+        });
+
+        let msg = InstantiateMsg {
+            aust_address: "aust".to_string(),
+            output_denom: "uosmo".to_string(),
+            max_rate_age: None,
+            verify_cw20: None,
+            fail_closed: None,
+            wrapper_token: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // 1000 aUST into pool 5 credits 1200 OSMO
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: ALICE.to_string(),
+            amount: Uint128::from(1_000_u64),
+            msg: to_binary(&ReceiveMsg::DepositToPool { pool_id: 5 }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("aust", &[]), msg).unwrap();
+
+        // the flat balance cannot reach pool funds
+        let msg = ExecuteMsg::Redeem {
+            amount: Uint128::from(500_u64),
+            recipient: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(ALICE, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientBalance {}));
+
+        // redeem 500 aUST out of pool 5
+        let msg = ExecuteMsg::RedeemFromPool {
+            pool_id: 5,
+            amount: Uint128::from(500_u64),
+            recipient: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info(ALICE, &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "aust".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: ALICE.to_string(),
+                    amount: Uint128::from(500_u64),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        let value = query_pool_balance(deps.as_ref(), 5, ALICE.to_string()).unwrap();
+        assert_eq!(value.amount.amount, Uint128::from(600_u64));
+
+        // cannot redeem more than the pool balance
+        let msg = ExecuteMsg::RedeemFromPool {
+            pool_id: 5,
+            amount: Uint128::from(501_u64),
+            recipient: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(ALICE, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientBalance {}));
+    }
+
+    #[test]
+    fn receive_redeem() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        // anchor reports a 1.20 exchange rate
+        deps.querier.update_wasm(|_| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&EpochStateResponse {
+                    exchange_rate: Decimal256::from_str("1.20").unwrap(),
+                    aterra_supply: Uint256::zero(),
+                })
+                .unwrap(),
+            ))
+        });
+
+        let msg = InstantiateMsg {
+            aust_address: "aust".to_string(),
+            output_denom: "uosmo".to_string(),
+            max_rate_age: None,
+            verify_cw20: None,
+            fail_closed: None,
+            wrapper_token: Some("wrapper".to_string()),
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info(ALICE, &coins(1_200, "uosmo"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        // alice sends 100 wrapper tokens to redeem 100 aUST
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: ALICE.to_string(),
+            amount: Uint128::from(100_u64),
+            msg: to_binary(&ReceiveMsg::Redeem {}).unwrap(),
+        });
+
+        // any other contract cannot redeem on alice's behalf
+        let info = mock_info(HACKER, &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let info = mock_info("wrapper", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "aust".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: ALICE.to_string(),
+                    amount: Uint128::from(100_u64),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "wrapper".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Burn {
+                    amount: Uint128::from(100_u64),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        // ledger is debited at the exchange rate, nothing is credited
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetBalance {
+                address: ALICE.to_string(),
+            },
+        )
+        .unwrap();
+        let value: BalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(value.amount.amount, Uint128::from(1_080_u64));
+        assert!(!USER_RAW_AUST.has(deps.as_ref().storage, &Addr::unchecked(ALICE)));
+    }
+
+    #[test]
+    fn deposit_history() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let set_rate = |deps: &mut OwnedDeps<_, _, MockQuerier>, rate: &'static str| {
+            deps.querier.update_wasm(move |_| {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&EpochStateResponse {
+                        exchange_rate: Decimal256::from_str(rate).unwrap(),
+                        aterra_supply: Uint256::zero(),
+                    })
+                    .unwrap(),
+                ))
+            });
+        };
+
+        let msg = InstantiateMsg {
+            aust_address: "aust".to_string(),
+            output_denom: "uosmo".to_string(),
+            max_rate_age: None,
+            verify_cw20: None,
+            fail_closed: None,
+            wrapper_token: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // two deposits at different rates and heights
+        let mut env = mock_env();
+        for (rate, amount) in [("1.20", 1_000_u64), ("1.50", 200)] {
+            set_rate(&mut deps, rate);
+            env.block.height += 1;
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: ALICE.to_string(),
+                amount: Uint128::from(amount),
+                msg: to_binary(&ReceiveMsg::Deposit { min_ust_out: None }).unwrap(),
+            });
+            let _res = execute(deps.as_mut(), env.clone(), mock_info("aust", &[]), msg).unwrap();
+        }
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetDepositHistory {
+                address: ALICE.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: DepositHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.deposits,
+            vec![
+                DepositRecord {
+                    id: 0,
+                    aust_amount: Uint128::from(1_000_u64),
+                    ust_credited: Uint128::from(1_200_u64),
+                    exchange_rate: Decimal256::from_str("1.20").unwrap(),
+                    block_height: mock_env().block.height + 1,
+                },
+                DepositRecord {
+                    id: 1,
+                    aust_amount: Uint128::from(200_u64),
+                    ust_credited: Uint128::from(300_u64),
+                    exchange_rate: Decimal256::from_str("1.50").unwrap(),
+                    block_height: mock_env().block.height + 2,
+                },
+            ]
+        );
+
+        // paginate past the first record
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetDepositHistory {
+                address: ALICE.to_string(),
+                start_after: Some(0),
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: DepositHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(value.deposits.len(), 1);
+        assert_eq!(value.deposits[0].id, 1);
+    }
+
+    #[test]
+    fn deposit_anchor_query_failure() {
+        for fail_closed in [None, Some(true), Some(false)] {
+            let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+            let msg = InstantiateMsg {
+                aust_address: "aust".to_string(),
+                output_denom: "uosmo".to_string(),
+                max_rate_age: None,
+                verify_cw20: None,
+                fail_closed,
+                wrapper_token: None,
+                event_prefix: None,
+            };
+            let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+            let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let deposit = || {
+                ExecuteMsg::Receive(Cw20ReceiveMsg {
+                    sender: ALICE.to_string(),
+                    amount: Uint128::from(1_000_u64),
+                    msg: to_binary(&ReceiveMsg::Deposit { min_ust_out: None }).unwrap(),
+                })
+            };
+
+            // first deposit records a rate of 1.20
+            deps.querier.update_wasm(|_| {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&EpochStateResponse {
+                        exchange_rate: Decimal256::from_str("1.20").unwrap(),
+                        aterra_supply: Uint256::zero(),
+                    })
+                    .unwrap(),
+                ))
+            });
+            let _res =
+                execute(deps.as_mut(), mock_env(), mock_info("aust", &[]), deposit()).unwrap();
+
+            // anchor becomes unavailable
+            deps.querier
+                .update_wasm(|_| SystemResult::Ok(ContractResult::Err("anchor down".to_string())));
+            let mut env = mock_env();
+            env.block.height += 1;
+            let res = execute(deps.as_mut(), env, mock_info("aust", &[]), deposit());
+
+            if fail_closed == Some(false) {
+                let res = res.unwrap();
+                assert!(res
+                    .attributes
+                    .iter()
+                    .any(|attr| attr.key == "exchange_rate" && attr.value == "1.2"));
+                assert!(res
+                    .attributes
+                    .iter()
+                    .any(|attr| attr.key == "rate_source" && attr.value == "fallback"));
+                assert_eq!(
+                    query_balance(deps.as_ref(), ALICE.to_string())
+                        .unwrap()
+                        .amount
+                        .amount,
+                    Uint128::from(2_400_u64)
+                );
+            } else {
+                assert!(res.is_err());
+                assert_eq!(
+                    query_balance(deps.as_ref(), ALICE.to_string())
+                        .unwrap()
+                        .amount
+                        .amount,
+                    Uint128::from(1_200_u64)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn deposit_fallback_rate_age() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            aust_address: "aust".to_string(),
+            output_denom: "uosmo".to_string(),
+            max_rate_age: Some(10),
+            verify_cw20: None,
+            fail_closed: Some(false),
+            wrapper_token: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let deposit = || {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: ALICE.to_string(),
+                amount: Uint128::from(1_000_u64),
+                msg: to_binary(&ReceiveMsg::Deposit { min_ust_out: None }).unwrap(),
+            })
+        };
+
+        deps.querier.update_wasm(|_| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&EpochStateResponse {
+                    exchange_rate: Decimal256::from_str("1.20").unwrap(),
+                    aterra_supply: Uint256::zero(),
+                })
+                .unwrap(),
+            ))
+        });
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("aust", &[]), deposit()).unwrap();
+
+        // anchor becomes unavailable
+        deps.querier
+            .update_wasm(|_| SystemResult::Ok(ContractResult::Err("anchor down".to_string())));
+
+        // fallback within the allowed age is not recorded as an observed rate
+        let mut env = mock_env();
+        env.block.height += 10;
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("aust", &[]),
+            deposit(),
+        )
+        .unwrap();
+        let history = query_rate_history(deps.as_ref(), None, None).unwrap();
+        assert_eq!(
+            history.rates,
+            vec![(
+                mock_env().block.height,
+                Decimal256::from_str("1.20").unwrap()
+            )]
+        );
+
+        // fallback older than the allowed age is rejected
+        env.block.height += 1;
+        let err = execute(deps.as_mut(), env, mock_info("aust", &[]), deposit()).unwrap_err();
+        assert!(matches!(err, ContractError::StaleRate {}));
+    }
+
+    /// helper function to setup aust and ctf contract and return the addresses
+    fn setup_contracts(app: &mut App) -> (Addr, Addr) {
+        setup_contracts_with(app, "uosmo", None, None)
+    }
+
+    /// same as `setup_contracts` with a custom output denom and anchor exchange rate
+    fn setup_contracts_with(
+        app: &mut App,
+        output_denom: &str,
+        exchange_rate: Option<Decimal256>,
+        max_rate_age: Option<u64>,
+    ) -> (Addr, Addr) {
+        // create mock anchor contract box
+        fn aust_contract() -> Box<dyn Contract<Empty>> {
+            let contract = ContractWrapper::new(
+                mock_anchor::execute,
+                mock_anchor::instantiate,
+                mock_anchor::query,
+            );
+            Box::new(contract)
+        }
+
+        // create ctf contract box
+        fn ctf_contract() -> Box<dyn Contract<Empty>> {
+            let contract = ContractWrapper::new(
+                crate::contract::execute,
+                crate::contract::instantiate,
+                crate::contract::query,
+            );
+            Box::new(contract)
+        }
 
         // store aust and ctf code id
         let aust_id = app.store_code(aust_contract());
         let ctf_id = app.store_code(ctf_contract());
 
         // mock anchor init msg
-        let msg = AnchorInstantiateMsg {};
+        let msg = AnchorInstantiateMsg { exchange_rate };
 
         // init aust contract
         let aust_init = app
@@ -321,6 +1409,12 @@ mod tests {
         // ctf contract init msg
         let msg = InstantiateMsg {
             aust_address: aust_init.to_string(), // use initialized aust contract addr
+            output_denom: output_denom.to_string(),
+            max_rate_age,
+            verify_cw20: None,
+            fail_closed: None,
+            wrapper_token: None,
+            event_prefix: None,
         };
 
         // mint tokens to admin
@@ -370,6 +1464,197 @@ mod tests {
         assert_eq!(res.exchange_rate, Decimal256::from_str("1.20").unwrap());
     }
 
+    #[test]
+    fn init_verify_cw20() {
+        fn cw20_contract() -> Box<dyn Contract<Empty>> {
+            let contract = ContractWrapper::new(
+                cw20_base::contract::execute,
+                cw20_base::contract::instantiate,
+                cw20_base::contract::query,
+            );
+            Box::new(contract)
+        }
+
+        fn aust_contract() -> Box<dyn Contract<Empty>> {
+            let contract = ContractWrapper::new(
+                mock_anchor::execute,
+                mock_anchor::instantiate,
+                mock_anchor::query,
+            );
+            Box::new(contract)
+        }
+
+        fn ctf_contract() -> Box<dyn Contract<Empty>> {
+            let contract = ContractWrapper::new(
+                crate::contract::execute,
+                crate::contract::instantiate,
+                crate::contract::query,
+            );
+            Box::new(contract)
+        }
+
+        let mut app = App::default();
+        let cw20_id = app.store_code(cw20_contract());
+        let aust_id = app.store_code(aust_contract());
+        let ctf_id = app.store_code(ctf_contract());
+
+        let msg = cw20_base::msg::InstantiateMsg {
+            name: "Anchor Terra USD".to_string(),
+            symbol: "aUST".to_string(),
+            decimals: 6,
+            initial_balances: vec![],
+            mint: None,
+            marketing: None,
+        };
+        let cw20_init = app
+            .instantiate_contract(
+                cw20_id,
+                Addr::unchecked(ADMIN_ADDR),
+                &msg,
+                &[],
+                "cw20",
+                None,
+            )
+            .unwrap();
+
+        // the mock anchor market does not answer cw20 queries
+        let msg = AnchorInstantiateMsg {
+            exchange_rate: None,
+        };
+        let anchor_init = app
+            .instantiate_contract(
+                aust_id,
+                Addr::unchecked(ADMIN_ADDR),
+                &msg,
+                &[],
+                "anchor",
+                None,
+            )
+            .unwrap();
+
+        app.sudo(SudoMsg::Bank({
+            BankSudo::Mint {
+                to_address: ADMIN_ADDR.to_string(),
+                amount: coins(2_000, "uosmo"),
+            }
+        }))
+        .unwrap();
+
+        for (aust_address, is_cw20) in [(anchor_init, false), (cw20_init, true)] {
+            let msg = InstantiateMsg {
+                aust_address: aust_address.to_string(),
+                output_denom: "uosmo".to_string(),
+                max_rate_age: None,
+                verify_cw20: Some(true),
+                fail_closed: None,
+                wrapper_token: None,
+                event_prefix: None,
+            };
+            let res = app.instantiate_contract(
+                ctf_id,
+                Addr::unchecked(ADMIN_ADDR),
+                &msg,
+                &coins(1_000, "uosmo"),
+                "ctf",
+                None,
+            );
+            if is_cw20 {
+                res.unwrap();
+            } else {
+                let err = res.unwrap_err().downcast::<ContractError>().unwrap();
+                assert!(matches!(err, ContractError::NotACw20 {}));
+            }
+        }
+    }
+
+    #[test]
+    fn simulate_redeem() {
+        let mut app = App::default();
+        let (_, ctf_init) = setup_contracts(&mut app);
+
+        // 1200 UST at the mock's 1.2 rate is 1000 aUST
+        let res: SimulateRedeemResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &ctf_init,
+                &QueryMsg::SimulateRedeem {
+                    ust_amount: Uint128::from(1200_u64),
+                },
+            )
+            .unwrap();
+        assert_eq!(res.aust_amount, Uint128::from(1000_u64));
+        assert_eq!(res.exchange_rate, Decimal256::from_str("1.20").unwrap());
+
+        // zero rate is rejected
+        let mut app = App::default();
+        let (_, ctf_init) = setup_contracts_with(&mut app, "uosmo", Some(Decimal256::zero()), None);
+        let err = app
+            .wrap()
+            .query_wasm_smart::<SimulateRedeemResponse>(
+                &ctf_init,
+                &QueryMsg::SimulateRedeem {
+                    ust_amount: Uint128::from(1200_u64),
+                },
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid exchange rate"));
+    }
+
+    #[test]
+    fn will_overflow() {
+        let mut app = App::default();
+        let (_, ctf_init) = setup_contracts(&mut app);
+
+        let will_overflow = |aust_amount: Uint128| -> bool {
+            app.wrap()
+                .query_wasm_smart(
+                    &ctf_init,
+                    &QueryMsg::WillOverflow {
+                        aust_amount,
+                        block_height: None,
+                    },
+                )
+                .unwrap()
+        };
+
+        // 1.2x of a normal amount fits
+        assert!(!will_overflow(Uint128::from(1_000_u64)));
+
+        // 1.2x of the maximum does not
+        assert!(will_overflow(Uint128::MAX));
+    }
+
+    #[test]
+    fn test_aust_query_requires_height() {
+        let mut app = App::default();
+        let (aust_init, _) = setup_contracts(&mut app);
+
+        // mock anchor rejects queries without a concrete height
+        let err = app
+            .wrap()
+            .query_wasm_smart::<EpochStateResponse>(
+                &aust_init,
+                &AnchorQueryMsg::EpochState {
+                    block_height: None,
+                    distributed_interest: None,
+                },
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("Missing block height"));
+
+        let res: EpochStateResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &aust_init,
+                &AnchorQueryMsg::EpochState {
+                    block_height: Some(app.block_info().height),
+                    distributed_interest: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.exchange_rate, Decimal256::from_str("1.20").unwrap());
+    }
+
     #[test]
     fn aust_deposit() {
         let mut app = App::default();
@@ -379,7 +1664,7 @@ mod tests {
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: ALICE.to_string(),
             amount: Uint128::from(1_000_u64),
-            msg: to_binary(&ReceiveMsg::Deposit {}).unwrap(),
+            msg: to_binary(&ReceiveMsg::Deposit { min_ust_out: None }).unwrap(),
         });
 
         // execute msg
@@ -406,6 +1691,458 @@ mod tests {
         assert_eq!(res.amount.amount, Uint128::from(1_200_u64)); // 1_000 aUST * 1.20 exchange rate = 1_200 OSMO
     }
 
+    #[test]
+    fn exchange_rate_history() {
+        let mut app = App::default();
+        let (aust_init, ctf_init) = setup_contracts(&mut app);
+
+        // deposits at two different heights
+        let start_height = app.block_info().height;
+        for _ in 0..2 {
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: ALICE.to_string(),
+                amount: Uint128::from(1_000_u64),
+                msg: to_binary(&ReceiveMsg::Deposit { min_ust_out: None }).unwrap(),
+            });
+            app.execute_contract(aust_init.clone(), ctf_init.clone(), &msg, &[])
+                .unwrap();
+            app.update_block(|block| block.height += 1);
+        }
+
+        let res: RateHistoryResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &ctf_init,
+                &QueryMsg::GetExchangeRateHistory {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        let rate = Decimal256::from_str("1.20").unwrap();
+        assert_eq!(
+            res.rates,
+            vec![(start_height, rate), (start_height + 1, rate)]
+        );
+
+        // paginate past the first height
+        let res: RateHistoryResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &ctf_init,
+                &QueryMsg::GetExchangeRateHistory {
+                    start_after: Some(start_height),
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.rates, vec![(start_height + 1, rate)]);
+    }
+
+    #[test]
+    fn aust_deposit_batch() {
+        let mut app = App::default();
+        let (aust_init, ctf_init) = setup_contracts(&mut app);
+
+        let batch = |amount: u64, deposits: Vec<(String, Uint128)>| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: ALICE.to_string(),
+                amount: Uint128::from(amount),
+                msg: to_binary(&ReceiveMsg::DepositBatch { deposits }).unwrap(),
+            })
+        };
+        let deposits = vec![
+            (ALICE.to_string(), Uint128::from(1_000_u64)),
+            ("bob".to_string(), Uint128::from(500_u64)),
+            (ALICE.to_string(), Uint128::from(10_u64)),
+        ];
+        let msg = batch(1_510, deposits.clone());
+
+        // only the aUST token may forward a batch
+        let err = app
+            .execute_contract(Addr::unchecked(HACKER), ctf_init.clone(), &msg, &[])
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap().to_string(),
+            "Unauthorized"
+        );
+
+        // entries must add up to the received amount
+        let err = app
+            .execute_contract(
+                aust_init.clone(),
+                ctf_init.clone(),
+                &batch(1_000, deposits),
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::BatchAmountMismatch {}
+        ));
+
+        // entry count is capped
+        let deposits = vec![(ALICE.to_string(), Uint128::from(1_u64)); 51];
+        let err = app
+            .execute_contract(
+                aust_init.clone(),
+                ctf_init.clone(),
+                &batch(51, deposits),
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::BatchTooLarge { max: 50 }
+        ));
+
+        // single rate applied to every entry
+        let res = app
+            .execute_contract(aust_init, ctf_init.clone(), &msg, &[])
+            .unwrap();
+        assert!(res.has_event(
+            &Event::new("wasm")
+                .add_attribute("method", "deposit_batch")
+                .add_attribute("exchange_rate", "1.2")
+                .add_attribute("deposits", "3")
+                .add_attribute("total_amount", "1812")
+        ));
+
+        for (address, amount) in [(ALICE, 1_212_u64), ("bob", 600)] {
+            let res: BalanceResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    &ctf_init,
+                    &QueryMsg::GetBalance {
+                        address: address.to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.amount.amount, Uint128::from(amount));
+        }
+    }
+
+    #[test]
+    fn aggregate_interest() {
+        let mut app = App::default();
+        let (aust_init, ctf_init) = setup_contracts(&mut app);
+
+        // alice and bob deposit aust
+        for (sender, amount) in [(ALICE, 1_000_u64), ("bob", 500_u64)] {
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: sender.to_string(),
+                amount: Uint128::from(amount),
+                msg: to_binary(&ReceiveMsg::Deposit { min_ust_out: None }).unwrap(),
+            });
+            app.borrow_mut()
+                .execute_contract(aust_init.clone(), ctf_init.clone(), &msg, &[])
+                .unwrap();
+        }
+
+        let res: AggregateInterestResponse = app
+            .borrow_mut()
+            .wrap()
+            .query_wasm_smart(&ctf_init, &QueryMsg::GetAggregateInterest {})
+            .unwrap();
+
+        // 1_500 aUST * 1.20 exchange rate = 1_800 OSMO
+        assert_eq!(res.total_raw_aust, Uint128::from(1_500_u64));
+        assert_eq!(res.total_credited_ust, Uint128::from(1_800_u64));
+        assert_eq!(res.total_interest, Uint128::from(300_u64));
+    }
+
+    #[test]
+    fn aust_deposit_min_ust_out() {
+        let mut app = App::default();
+        let (aust_init, ctf_init) = setup_contracts(&mut app);
+
+        let deposit = |min_ust_out: u64| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: ALICE.to_string(),
+                amount: Uint128::from(1_000_u64),
+                msg: to_binary(&ReceiveMsg::Deposit {
+                    min_ust_out: Some(Uint128::from(min_ust_out)),
+                })
+                .unwrap(),
+            })
+        };
+
+        // 1000 aUST at 1.20 credits 1200, below the requested minimum
+        let err = app
+            .borrow_mut()
+            .execute_contract(aust_init.clone(), ctf_init.clone(), &deposit(1_300), &[])
+            .unwrap_err();
+        let err = err.downcast::<ContractError>().unwrap();
+        assert!(matches!(
+            err,
+            ContractError::SlippageExceeded { got, min }
+                if got == Uint128::from(1_200_u64) && min == Uint128::from(1_300_u64)
+        ));
+
+        let balance = |app: &App| -> Uint128 {
+            let res: BalanceResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    &ctf_init,
+                    &QueryMsg::GetBalance {
+                        address: ALICE.to_string(),
+                    },
+                )
+                .unwrap();
+            res.amount.amount
+        };
+        assert_eq!(balance(&app), Uint128::zero());
+
+        // within tolerance
+        app.borrow_mut()
+            .execute_contract(aust_init.clone(), ctf_init.clone(), &deposit(1_100), &[])
+            .unwrap();
+        assert_eq!(balance(&app), Uint128::from(1_200_u64));
+    }
+
+    #[test]
+    fn aust_deposit_stale_rate() {
+        let mut app = App::default();
+        let (aust_init, ctf_init) = setup_contracts_with(&mut app, "uosmo", None, Some(100));
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: ALICE.to_string(),
+            amount: Uint128::from(1_000_u64),
+            msg: to_binary(&ReceiveMsg::Deposit { min_ust_out: None }).unwrap(),
+        });
+        app.borrow_mut()
+            .execute_contract(aust_init.clone(), ctf_init.clone(), &msg, &[])
+            .unwrap();
+
+        // a quiet period does not lock deposits, each one observes a fresh rate
+        app.update_block(|block| block.height += 201);
+        app.borrow_mut()
+            .execute_contract(aust_init, ctf_init.clone(), &msg, &[])
+            .unwrap();
+
+        let rate_at = |app: &App, block_height: u64| {
+            app.wrap().query_wasm_smart::<EpochStateResponse>(
+                &ctf_init,
+                &QueryMsg::GetAnchorRate {
+                    block_height: Some(block_height),
+                    distributed_interest: None,
+                },
+            )
+        };
+
+        // rates within the allowed age of the current block
+        let height = app.block_info().height;
+        rate_at(&app, height).unwrap();
+        rate_at(&app, height - 100).unwrap();
+
+        // a rate further back than the allowed age
+        let err = rate_at(&app, height - 101).unwrap_err();
+        assert!(err.to_string().contains("Stale exchange rate"));
+    }
+
+    #[test]
+    fn aust_deposit_credit_too_small() {
+        let mut app = App::default();
+        let (aust_init, ctf_init) = setup_contracts_with(
+            &mut app,
+            "uosmo",
+            Some(Decimal256::from_str("0.5").unwrap()),
+            None,
+        );
+
+        // 1 aUST * 0.5 exchange rate truncates to 0 OSMO
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: ALICE.to_string(),
+            amount: Uint128::from(1_u64),
+            msg: to_binary(&ReceiveMsg::Deposit { min_ust_out: None }).unwrap(),
+        });
+        let err = app
+            .borrow_mut()
+            .execute_contract(aust_init, ctf_init, &msg, &[])
+            .unwrap_err();
+        assert_eq!(err.root_cause().to_string(), "Credit too small");
+    }
+
+    #[test]
+    fn aust_deposit_to_pool() {
+        let mut app = App::default();
+        let (aust_init, ctf_init) = setup_contracts(&mut app);
+
+        // deposit aust into pool 5
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: ALICE.to_string(),
+            amount: Uint128::from(1_000_u64),
+            msg: to_binary(&ReceiveMsg::DepositToPool { pool_id: 5 }).unwrap(),
+        });
+        app.borrow_mut()
+            .execute_contract(aust_init, ctf_init.clone(), &msg, &[])
+            .unwrap();
+
+        let res: BalanceResponse = app
+            .borrow_mut()
+            .wrap()
+            .query_wasm_smart(
+                &ctf_init,
+                &QueryMsg::GetPoolBalance {
+                    pool_id: 5,
+                    address: ALICE.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(res.amount.amount, Uint128::from(1_200_u64));
+
+        // other pools are untouched
+        let res: BalanceResponse = app
+            .borrow_mut()
+            .wrap()
+            .query_wasm_smart(
+                &ctf_init,
+                &QueryMsg::GetPoolBalance {
+                    pool_id: 1,
+                    address: ALICE.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(res.amount.amount, Uint128::zero());
+    }
+
+    #[test]
+    fn aust_deposit_withdraw() {
+        let mut app = App::default();
+        let (aust_init, ctf_init) = setup_contracts(&mut app);
+
+        // aust deposit msg
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: ALICE.to_string(),
+            amount: Uint128::from(1_000_u64),
+            msg: to_binary(&ReceiveMsg::Deposit { min_ust_out: None }).unwrap(),
+        });
+        app.borrow_mut()
+            .execute_contract(aust_init, ctf_init.clone(), &msg, &[])
+            .unwrap();
+
+        // alice withdraws part of the credited balance
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::from(500_u64),
+        };
+        app.borrow_mut()
+            .execute_contract(Addr::unchecked(ALICE), ctf_init.clone(), &msg, &[])
+            .unwrap();
+
+        // verify bank transfer
+        let res = app.wrap().query_balance(ALICE, "uosmo").unwrap();
+        assert_eq!(res.amount, Uint128::from(500_u64));
+
+        // verify remaining ledger balance
+        let res: BalanceResponse = app
+            .borrow_mut()
+            .wrap()
+            .query_wasm_smart(
+                &ctf_init,
+                &QueryMsg::GetBalance {
+                    address: ALICE.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(res.amount.amount, Uint128::from(700_u64));
+
+        // cannot withdraw more than the remaining balance
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::from(701_u64),
+        };
+        let err = app
+            .borrow_mut()
+            .execute_contract(Addr::unchecked(ALICE), ctf_init, &msg, &[])
+            .unwrap_err();
+        assert_eq!(err.root_cause().to_string(), "Insufficient balance");
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid output denom")]
+    fn invalid_output_denom() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+        let msg = InstantiateMsg {
+            aust_address: "terra1hzh9vpxhsk8253se0vv5jj6etdvxu3nv8z07zu".to_string(),
+            output_denom: "1!".to_string(),
+            max_rate_age: None,
+            verify_cw20: None,
+            fail_closed: None,
+            wrapper_token: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn aust_deposit_custom_denom() {
+        let mut app = App::default();
+        let (aust_init, ctf_init) = setup_contracts_with(&mut app, "uusd", None, None);
+
+        // aust deposit msg
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: ALICE.to_string(),
+            amount: Uint128::from(1_000_u64),
+            msg: to_binary(&ReceiveMsg::Deposit { min_ust_out: None }).unwrap(),
+        });
+
+        app.borrow_mut()
+            .execute_contract(aust_init, ctf_init.clone(), &msg, &[])
+            .unwrap();
+
+        let res: BalanceResponse = app
+            .borrow_mut()
+            .wrap()
+            .query_wasm_smart(
+                &ctf_init,
+                &QueryMsg::GetBalance {
+                    address: ALICE.to_string(),
+                },
+            )
+            .unwrap();
+
+        // exchange rate still applies, credited in the configured denom
+        assert_eq!(res.amount.denom, "uusd".to_string());
+        assert_eq!(res.amount.amount, Uint128::from(1_200_u64));
+    }
+
+    #[test]
+    fn withdraw_prefixed_event() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let instantiate_msg = |event_prefix: &str| InstantiateMsg {
+            aust_address: "terra1hzh9vpxhsk8253se0vv5jj6etdvxu3nv8z07zu".to_string(),
+            output_denom: "uosmo".to_string(),
+            max_rate_age: None,
+            verify_cw20: None,
+            fail_closed: None,
+            wrapper_token: None,
+            event_prefix: Some(event_prefix.to_string()),
+        };
+
+        // an explicit empty prefix is a misconfiguration, omit it instead
+        let info = mock_info("creator", &coins(1000, "uosmo"));
+        let err = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg("")).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidEventPrefix {}));
+
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let msg = instantiate_msg("anchor4");
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::from(100_u64),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "anchor4_withdraw");
+        assert_eq!(res.events[0].attributes, res.attributes);
+    }
+
     #[test]
     fn exploit() {
         let mut app = App::default();
@@ -415,7 +2152,7 @@ mod tests {
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: HACKER.to_string(),
             amount: Uint128::from(10_000_u64),
-            msg: to_binary(&ReceiveMsg::Deposit {}).unwrap(),
+            msg: to_binary(&ReceiveMsg::Deposit { min_ust_out: None }).unwrap(),
         });
 
         // since there's no cw20 addr check, an attacker can simply create a new token and send to the contract
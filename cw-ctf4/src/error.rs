@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,6 +9,33 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Credit too small")]
+    CreditTooSmall {},
+
+    #[error("Stale exchange rate")]
+    StaleRate {},
+
+    #[error("Insufficient balance")]
+    InsufficientBalance {},
+
+    #[error("Balance overflow")]
+    BalanceOverflow {},
+
+    #[error("Not a cw20 contract")]
+    NotACw20 {},
+
+    #[error("Slippage exceeded, got {got} below minimum {min}")]
+    SlippageExceeded { got: Uint128, min: Uint128 },
+
+    #[error("Batch exceeds {max} deposits")]
+    BatchTooLarge { max: u32 },
+
+    #[error("Batch amounts do not match the received amount")]
+    BatchAmountMismatch {},
+
+    #[error("Invalid event prefix")]
+    InvalidEventPrefix {},
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
     // Add any other custom errors you like here.
@@ -5,24 +5,34 @@ use crate::msg::{AnchorQueryMsg as QueryMsg, EpochStateResponse};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint256,
+    to_binary, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    Uint256,
 };
+use cw_storage_plus::Item;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+/// exchange rate override, defaults to 1.20
+pub const EXCHANGE_RATE: Item<Decimal256> = Item::new("exchange_rate");
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    pub exchange_rate: Option<Decimal256>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ExecuteMsg {}
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
     _info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    if let Some(exchange_rate) = msg.exchange_rate {
+        EXCHANGE_RATE.save(deps.storage, &exchange_rate)?;
+    }
     Ok(Response::new())
 }
 
@@ -51,12 +61,21 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
 }
 
 fn query_epoch_state(
-    _deps: Deps,
-    _block_height: Option<u64>,
+    deps: Deps,
+    block_height: Option<u64>,
     _distributed_interest: Option<Uint256>,
 ) -> StdResult<EpochStateResponse> {
+    // a real node may serve stale data without a height, so require one
+    if block_height.is_none() {
+        return Err(StdError::generic_err("Missing block height"));
+    }
+
+    let exchange_rate = match EXCHANGE_RATE.may_load(deps.storage)? {
+        Some(exchange_rate) => exchange_rate,
+        None => Decimal256::from_str("1.20")?, // good old days.. :(
+    };
     Ok(EpochStateResponse {
-        exchange_rate: Decimal256::from_str("1.20")?, // good old days.. :(
+        exchange_rate,
         aterra_supply: Uint256::from(0_u64),
     })
 }
@@ -3,23 +3,45 @@ use cw20::Cw20ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::state::DepositRecord;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub aust_address: String,
+    pub output_denom: String,
+    pub max_rate_age: Option<u64>,
+    pub verify_cw20: Option<bool>,
+    pub fail_closed: Option<bool>,
+    pub wrapper_token: Option<String>,
+    pub event_prefix: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     Deposit {},
-    Withdraw { amount: Uint128 },
+    Withdraw {
+        amount: Uint128,
+    },
     Receive(Cw20ReceiveMsg),
+    Redeem {
+        amount: Uint128,
+        recipient: Option<String>,
+    },
+    RedeemFromPool {
+        pool_id: u64,
+        amount: Uint128,
+        recipient: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ReceiveMsg {
-    Deposit {},
+    Deposit { min_ust_out: Option<Uint128> },
+    DepositToPool { pool_id: u64 },
+    DepositBatch { deposits: Vec<(String, Uint128)> },
+    Redeem {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -32,6 +54,53 @@ pub enum QueryMsg {
         block_height: Option<u64>,
         distributed_interest: Option<Uint256>,
     },
+    GetAggregateInterest {},
+    GetPoolBalance {
+        pool_id: u64,
+        address: String,
+    },
+    SimulateRedeem {
+        ust_amount: Uint128,
+    },
+    GetDepositHistory {
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    WillOverflow {
+        aust_amount: Uint128,
+        block_height: Option<u64>,
+    },
+    GetExchangeRateHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositHistoryResponse {
+    pub deposits: Vec<DepositRecord>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateHistoryResponse {
+    /// (block height, exchange rate) pairs
+    pub rates: Vec<(u64, Decimal256)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SimulateRedeemResponse {
+    pub aust_amount: Uint128,
+    pub exchange_rate: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AggregateInterestResponse {
+    pub total_raw_aust: Uint128,
+    pub total_credited_ust: Uint128,
+    pub total_interest: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -1,5 +1,45 @@
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal256, Uint128};
 use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositRecord {
+    /// per-user deposit id
+    pub id: u64,
+    /// aUST received
+    pub aust_amount: Uint128,
+    /// amount credited in the output denom
+    pub ust_credited: Uint128,
+    /// anchor rate applied to the deposit
+    pub exchange_rate: Decimal256,
+    /// block height of the deposit
+    pub block_height: u64,
+}
 
 pub const AUST_ADDRESS: Item<Addr> = Item::new("aust_address");
+pub const OUTPUT_DENOM: Item<String> = Item::new("output_denom");
 pub const USER_BALANCE: Map<&Addr, Uint128> = Map::new("user_balance");
+pub const USER_RAW_AUST: Map<&Addr, Uint128> = Map::new("user_raw_aust");
+pub const POOL_BALANCE: Map<(u64, &Addr), Uint128> = Map::new("pool_balance");
+pub const USER_CREDITED_UST: Map<&Addr, Uint128> = Map::new("user_credited_ust");
+
+/// (user, per-user deposit id) to aUST deposit record
+pub const DEPOSIT_HISTORY: Map<(&Addr, u64), DepositRecord> = Map::new("deposit_history");
+/// number of aUST deposits recorded per user
+pub const DEPOSIT_HISTORY_COUNT: Map<&Addr, u64> = Map::new("deposit_history_count");
+
+/// reject deposits when the anchor query fails instead of using the last recorded rate
+pub const FAIL_CLOSED: Item<bool> = Item::new("fail_closed");
+
+/// cw20 wrapper token burned on redemption, redeeming through receive is disabled when unset
+pub const WRAPPER_TOKEN: Item<Addr> = Item::new("wrapper_token");
+
+/// block height to the anchor exchange rate used by deposits at that height
+pub const RATE_HISTORY: Map<u64, Decimal256> = Map::new("rate_history");
+
+/// maximum block gap allowed between anchor rate lookups, unchecked when unset
+pub const MAX_RATE_AGE: Item<u64> = Item::new("max_rate_age");
+
+/// prefix for custom event types, empty when not configured
+pub const EVENT_PREFIX: Item<String> = Item::new("event_prefix");
@@ -1,39 +1,113 @@
+use std::collections::BTreeMap;
+
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, NextLockdropId, QueryMsg};
-use crate::state::{Lockdrop, LOCKDROP_COUNT, USER_LOCKDROP};
+use crate::helpers::{add_prefixed_event, validate_event_prefix};
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, LockdropCountsResponse, LockdropsResponse, MigrateMsg,
+    NextLockdropId, PreviewResponse, ProjectedPayoutResponse, QueryMsg, SeedResponse,
+    SolvencyRatioResponse,
+};
+use crate::state::{
+    Lockdrop, ADMIN, EVENT_PREFIX, GUARANTEE_PRINCIPAL, LOCKDROP_COUNT, LOCK_SECONDS,
+    MAX_LOCKDROPS_PER_OWNER, MIN_SWEEP_GRACE, OWNER_LOCKDROP_COUNT, RESERVE, REWARD_POOL,
+    REWARD_TOKEN, SEED, SPLIT_PAYOUT, TOTAL_LOCKED, USER_LOCKDROP, WITHDRAWN_COUNT,
+};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo,
-    Response, StdError, StdResult, Uint128,
+    to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Order, Response, StdError, StdResult, Uint128, WasmMsg,
 };
+use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::Bound;
 
 /// minimum amount for lockdrop
 const MINIMUM_AMOUNT: u64 = 100;
 
-/// 24 hour locking time
+/// maximum lockdrop label length in bytes
+const MAX_LABEL_LENGTH: usize = 64;
+
+/// default minimum seed provided at instantiation
+const MINIMUM_SEED: u64 = 1000;
+
+/// default 24 hour locking time
 const LOCK_TIME: u64 = 24 * 60 * 60;
 
+/// default minimum grace before unclaimed lockdrops can be swept, one week
+const DEFAULT_SWEEP_GRACE: u64 = 7 * 24 * 60 * 60;
+
 /// reward bonus for users who locks their funds, 5% per day!
 const PONZI_BONUS: u64 = 105;
 
+/// no bonus for the instant unlock tier
+const INSTANT_BONUS: u64 = 100;
+
+/// default and maximum page size for paginated queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// maximum number of owners in the projected payout ranking
+const MAX_TOP_LIMIT: u32 = 50;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    // admin must provide 1000 uosmo when instantiating contract
-    if info.funds.len() != 1
-        || info.funds[0].denom != "uosmo"
-        || info.funds[0].amount != Uint128::from(1000_u64)
-    {
+    // admin must provide at least the minimum seed in uosmo, overpayment is kept
+    let min_seed = msg.min_seed.unwrap_or_else(|| Uint128::from(MINIMUM_SEED));
+    if info.funds.len() != 1 || info.funds[0].denom != "uosmo" || info.funds[0].amount < min_seed {
         return Err(ContractError::Std(StdError::generic_err(
             "Invalid instantiation",
         )));
     }
 
+    // we set ourself as admin
+    ADMIN.save(deps.storage, &info.sender)?;
+    SEED.save(deps.storage, &info.funds[0])?;
+
+    // locking time defaults to 24 hours, funds must stay locked past the deposit block
+    let lock_seconds = msg.lock_seconds.unwrap_or(LOCK_TIME);
+    if lock_seconds == 0 {
+        return Err(ContractError::InvalidLockTime {});
+    }
+    LOCK_SECONDS.save(deps.storage, &lock_seconds)?;
+
+    // optional cw20 minted for bonuses
+    if let Some(reward_token) = msg.reward_token {
+        REWARD_TOKEN.save(deps.storage, &deps.api.addr_validate(&reward_token)?)?;
+    }
+
+    // per-owner lockdrop cap, unlimited by default
+    MAX_LOCKDROPS_PER_OWNER.save(
+        deps.storage,
+        &msg.max_lockdrops_per_owner.unwrap_or_default(),
+    )?;
+
+    // bonuses may dip into principal unless guaranteed
+    GUARANTEE_PRINCIPAL.save(deps.storage, &msg.guarantee_principal.unwrap_or_default())?;
+
+    // principal and bonus are paid in a single send by default
+    SPLIT_PAYOUT.save(deps.storage, &msg.split_payout.unwrap_or_default())?;
+
+    // optional prefix for custom events
+    if let Some(event_prefix) = &msg.event_prefix {
+        validate_event_prefix(event_prefix)?;
+    }
+    EVENT_PREFIX.save(deps.storage, &msg.event_prefix.unwrap_or_default())?;
+
+    // sweeps must leave owners at least this long to claim
+    MIN_SWEEP_GRACE.save(
+        deps.storage,
+        &msg.min_sweep_grace.unwrap_or(DEFAULT_SWEEP_GRACE),
+    )?;
+    TOTAL_LOCKED.save(deps.storage, &Uint128::zero())?;
+
+    // instantiation funds seed the bonus reserve
+    RESERVE.save(deps.storage, &info.funds[0].amount)?;
+
     Ok(Response::new())
 }
 
@@ -44,13 +118,56 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    match msg {
-        ExecuteMsg::Deposit {} => try_deposit(deps, env, info),
+    let event_prefix = EVENT_PREFIX.may_load(deps.storage)?.unwrap_or_default();
+
+    let res = match msg {
+        ExecuteMsg::Deposit { label } => try_deposit(deps, env, info, label),
+        ExecuteMsg::DepositInstant {} => try_deposit_instant(deps, env, info),
         ExecuteMsg::Withdraw { lockdrop_ids } => try_withdraw(deps, env, info, lockdrop_ids),
+        ExecuteMsg::WithdrawAllMatured {} => try_withdraw_all_matured(deps, env, info),
+        ExecuteMsg::FundPool {} => try_fund_pool(deps, info),
+        ExecuteMsg::FundRewards {} => try_fund_rewards(deps, info),
+        ExecuteMsg::SweepExpired {
+            grace_seconds,
+            limit,
+        } => try_sweep_expired(deps, env, info, grace_seconds, limit),
+    }?;
+
+    Ok(add_prefixed_event(res, &event_prefix))
+}
+
+pub fn try_deposit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    label: Option<String>,
+) -> Result<Response, ContractError> {
+    // bound label storage
+    if label.as_ref().is_some_and(|l| l.len() > MAX_LABEL_LENGTH) {
+        return Err(ContractError::LabelTooLong {});
     }
+
+    let lock_seconds = LOCK_SECONDS.load(deps.storage)?;
+    create_lockdrop(deps, env, info, lock_seconds, PONZI_BONUS, label)
 }
 
-pub fn try_deposit(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+pub fn try_deposit_instant(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    // instant tier is withdrawable right away at 1:1
+    create_lockdrop(deps, env, info, 0, INSTANT_BONUS, None)
+}
+
+fn create_lockdrop(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lock_seconds: u64,
+    bonus_percent: u64,
+    label: Option<String>,
+) -> Result<Response, ContractError> {
     // validate uosmo sent
     if info.funds.len() != 1 || info.funds[0].denom != "uosmo" {
         return Err(ContractError::Std(StdError::generic_err(
@@ -65,6 +182,18 @@ pub fn try_deposit(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respons
         )));
     }
 
+    // bound lockdrops stored per owner
+    let owner_count = OWNER_LOCKDROP_COUNT
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    let max_per_owner = MAX_LOCKDROPS_PER_OWNER
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    if max_per_owner != 0 && owner_count >= max_per_owner {
+        return Err(ContractError::OwnerLockdropLimit {});
+    }
+    OWNER_LOCKDROP_COUNT.save(deps.storage, &info.sender, &(owner_count + 1))?;
+
     // retrieve and increment lockdrop id
     let mut lockdrop_id = LOCKDROP_COUNT.load(deps.storage).unwrap_or_default();
 
@@ -73,12 +202,22 @@ pub fn try_deposit(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respons
         id: lockdrop_id,
         owner: info.sender.clone(),
         amount: info.funds[0].amount,
-        unlock_time: env.block.time.plus_seconds(LOCK_TIME).seconds(),
+        unlock_time: env.block.time.plus_seconds(lock_seconds).seconds(),
+        bonus_percent,
+        label,
     };
 
     // save lockdrop info to storage
     USER_LOCKDROP.save(deps.storage, lockdrop_id, &new_lockdrop)?;
 
+    // track locked principal
+    let total_locked = TOTAL_LOCKED
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_add(new_lockdrop.amount)
+        .map_err(StdError::from)?;
+    TOTAL_LOCKED.save(deps.storage, &total_locked)?;
+
     // increment and save lockdrop count
     lockdrop_id += 1;
     LOCKDROP_COUNT.save(deps.storage, &lockdrop_id)?;
@@ -87,7 +226,11 @@ pub fn try_deposit(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respons
         .add_attribute("method", "deposit")
         .add_attribute("sender", info.sender.to_string())
         .add_attribute("amount", info.funds[0].amount)
-        .add_attribute("next_lockdrop_id", lockdrop_id.to_string()))
+        .add_attribute("next_lockdrop_id", lockdrop_id.to_string())
+        .add_attribute("lockdrop_id", new_lockdrop.id.to_string())
+        // unix seconds for off-chain unlock schedulers
+        .add_attribute("unlock_time", new_lockdrop.unlock_time.to_string())
+        .add_attribute("bonus_pct", (new_lockdrop.bonus_percent - 100).to_string()))
 }
 
 pub fn try_withdraw(
@@ -96,15 +239,29 @@ pub fn try_withdraw(
     info: MessageInfo,
     lockdrop_ids: Vec<u64>,
 ) -> Result<Response, ContractError> {
+    // reject empty requests up front
+    if lockdrop_ids.is_empty() {
+        return Err(ContractError::EmptyInput {});
+    }
+
     // amount to send to user
     let mut total_amount = Uint128::zero();
 
     // unlocked lockdrop vector
     let mut unlocked_lockdrops: Vec<Lockdrop> = vec![];
 
+    // ids that do not exist
+    let mut skipped_ids: Vec<String> = vec![];
+
     for id in lockdrop_ids {
         // load value from storage
-        let lockdrop_info = USER_LOCKDROP.load(deps.storage, id)?;
+        let lockdrop_info = match USER_LOCKDROP.may_load(deps.storage, id)? {
+            Some(lockdrop_info) => lockdrop_info,
+            None => {
+                skipped_ids.push(id.to_string());
+                continue;
+            }
+        };
 
         // verify owner and unlock time had passed
         if lockdrop_info.owner == info.sender
@@ -121,34 +278,429 @@ pub fn try_withdraw(
         )));
     }
 
+    // principal paid out, and principal and lockdrops removed from storage
+    let mut paid_principal = Uint128::zero();
+    let mut removed_principal = Uint128::zero();
+    let mut removed_count = 0_u64;
+
     // apply our p̶o̶n̶z̶i̶ reward bonus
     for lockdrop in unlocked_lockdrops {
-        let bonus_amount = lockdrop.amount * Decimal::percent(PONZI_BONUS);
-        total_amount += bonus_amount;
+        let bonus_amount = lockdrop
+            .amount
+            .checked_multiply_ratio(lockdrop.bonus_percent, 100_u64)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+        total_amount = total_amount
+            .checked_add(bonus_amount)
+            .map_err(StdError::from)?;
+        paid_principal = paid_principal
+            .checked_add(lockdrop.amount)
+            .map_err(StdError::from)?;
+        if USER_LOCKDROP.has(deps.storage, lockdrop.id) {
+            removed_principal = removed_principal
+                .checked_add(lockdrop.amount)
+                .map_err(StdError::from)?;
+            removed_count += 1;
+        }
+        USER_LOCKDROP.remove(deps.storage, lockdrop.id);
+    }
+
+    let total_locked = TOTAL_LOCKED.may_load(deps.storage)?.unwrap_or_default();
+    let reserve = RESERVE.may_load(deps.storage)?.unwrap_or_default();
+    let reward_pool = REWARD_POOL.may_load(deps.storage)?;
+    let reward_token = REWARD_TOKEN.may_load(deps.storage)?;
+
+    // bonus is minted instead of paid in uosmo when a reward token is set
+    let bonus = total_amount.saturating_sub(removed_principal);
+    let (bank_amount, bank_bonus) = match reward_token {
+        Some(_) => (removed_principal, Uint128::zero()),
+        None => (total_amount, bonus),
+    };
+
+    // with guaranteed principal the bonus absorbs any shortfall of the backing pool
+    let guarantee_principal = GUARANTEE_PRINCIPAL
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+
+    // guaranteed principal also means every principal paid out leaves storage
+    if guarantee_principal && paid_principal != removed_principal {
+        return Err(ContractError::AccountingDrift {});
+    }
+
+    let bonus_shortfall = match guarantee_principal {
+        true => bank_bonus.saturating_sub(reward_pool.unwrap_or(reserve)),
+        false => Uint128::zero(),
+    };
+    let bank_amount = bank_amount - bonus_shortfall;
+    let bank_bonus = bank_bonus - bonus_shortfall;
+
+    // payout can never exceed user principal plus reserve and reward pool
+    let available = total_locked
+        .checked_add(reserve)
+        .and_then(|v| v.checked_add(reward_pool.unwrap_or_default()))
+        .map_err(StdError::from)?;
+    if bank_amount > available {
+        return Err(ContractError::InsufficientFunds {});
+    }
+
+    // a funded reward pool must fully cover bonuses
+    if let Some(reward_pool) = reward_pool {
+        let reward_pool = reward_pool
+            .checked_sub(bank_bonus)
+            .map_err(|_| ContractError::InsufficientRewards {})?;
+        REWARD_POOL.save(deps.storage, &reward_pool)?;
+    }
+
+    // otherwise bonuses are drawn from the reserve first, any shortfall from principal
+    let reserve_draw = match reward_pool {
+        Some(_) => bank_bonus,
+        None => {
+            let reserve_draw = bank_bonus.min(reserve);
+            RESERVE.save(deps.storage, &(reserve - reserve_draw))?;
+            reserve_draw
+        }
+    };
+
+    // running total must cover the principal leaving storage
+    let total_locked = total_locked
+        .checked_sub(removed_principal + (bank_bonus - reserve_draw))
+        .map_err(|_| ContractError::AccountingDrift {})?;
+    TOTAL_LOCKED.save(deps.storage, &total_locked)?;
+
+    let withdrawn_count = WITHDRAWN_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    WITHDRAWN_COUNT.save(deps.storage, &(withdrawn_count + removed_count))?;
+
+    // free up the owner's lockdrop slots
+    let owner_count = OWNER_LOCKDROP_COUNT
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    OWNER_LOCKDROP_COUNT.save(
+        deps.storage,
+        &info.sender,
+        &owner_count.saturating_sub(removed_count),
+    )?;
+
+    // send rewards to user, principal first when split from the bonus
+    let split_payout = SPLIT_PAYOUT.may_load(deps.storage)?.unwrap_or_default();
+    let payouts = match split_payout {
+        true => vec![bank_amount - bank_bonus, bank_bonus],
+        false => vec![bank_amount],
+    };
+    let msgs = payouts
+        .into_iter()
+        .filter(|amount| !split_payout || !amount.is_zero())
+        .map(|amount| {
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![Coin {
+                    denom: "uosmo".to_string(),
+                    amount,
+                }],
+            })
+        });
+
+    let mut res = Response::new()
+        .add_messages(msgs)
+        .add_attribute("method", "withdraw")
+        .add_attribute("total_amount", total_amount)
+        .add_attribute("sender", info.sender.to_string());
+
+    // empty attribute values are rejected by the chain
+    if !skipped_ids.is_empty() {
+        res = res.add_attribute("skipped_ids", skipped_ids.join(","));
+    }
+    if !bonus_shortfall.is_zero() {
+        res = res.add_attribute("bonus_shortfall", bonus_shortfall);
+    }
+
+    // mint the bonus in the reward token
+    if let Some(reward_token) = reward_token {
+        if !bonus.is_zero() {
+            let mint = WasmMsg::Execute {
+                contract_addr: reward_token.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Mint {
+                    recipient: info.sender.to_string(),
+                    amount: bonus,
+                })?,
+                funds: vec![],
+            };
+            // a failed mint reverts the whole withdrawal
+            res = res.add_message(mint);
+        }
+        res = res.add_attribute("minted_bonus", bonus);
+    }
+
+    Ok(res)
+}
+
+pub fn try_withdraw_all_matured(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    // no owner index, scan every lockdrop
+    let lockdrop_ids = USER_LOCKDROP
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|v| {
+            v.as_ref().map_or(true, |(_, l)| {
+                l.owner == info.sender && env.block.time.seconds() >= l.unlock_time
+            })
+        })
+        .map(|v| Ok(v?.0))
+        .collect::<StdResult<Vec<u64>>>()?;
+
+    // nothing matured is not an input error
+    if lockdrop_ids.is_empty() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Nothing to withdraw!",
+        )));
+    }
+
+    try_withdraw(deps, env, info, lockdrop_ids)
+}
+
+/// forward the principal of lockdrops left unclaimed past the grace period to the admin
+pub fn try_sweep_expired(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    grace_seconds: u64,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    // load admin address from storage
+    let admin_addr = ADMIN.load(deps.storage)?;
+
+    // verify sender is admin
+    if info.sender != admin_addr {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // the admin cannot shorten the grace below the configured minimum
+    let min_grace = MIN_SWEEP_GRACE
+        .may_load(deps.storage)?
+        .unwrap_or(DEFAULT_SWEEP_GRACE);
+    if grace_seconds < min_grace {
+        return Err(ContractError::GraceTooShort { min: min_grace });
+    }
+
+    // no owner index, scan every lockdrop
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let now = env.block.time.seconds();
+    let expired = USER_LOCKDROP
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|v| {
+            v.as_ref().map_or(true, |(_, l)| {
+                l.unlock_time.saturating_add(grace_seconds) < now
+            })
+        })
+        .take(limit)
+        .map(|v| Ok(v?.1))
+        .collect::<StdResult<Vec<Lockdrop>>>()?;
+
+    if expired.is_empty() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Nothing to sweep!",
+        )));
+    }
+
+    // principal only, abandoned lockdrops forfeit their bonus
+    let mut swept_amount = Uint128::zero();
+    for lockdrop in &expired {
+        swept_amount = swept_amount
+            .checked_add(lockdrop.amount)
+            .map_err(StdError::from)?;
         USER_LOCKDROP.remove(deps.storage, lockdrop.id);
+
+        // free up the owner's lockdrop slot
+        let owner_count = OWNER_LOCKDROP_COUNT
+            .may_load(deps.storage, &lockdrop.owner)?
+            .unwrap_or_default();
+        OWNER_LOCKDROP_COUNT.save(
+            deps.storage,
+            &lockdrop.owner,
+            &owner_count.saturating_sub(1),
+        )?;
     }
 
-    // send rewards to user
+    let total_locked = TOTAL_LOCKED
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_sub(swept_amount)
+        .map_err(|_| ContractError::AccountingDrift {})?;
+    TOTAL_LOCKED.save(deps.storage, &total_locked)?;
+
+    let withdrawn_count = WITHDRAWN_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    WITHDRAWN_COUNT.save(deps.storage, &(withdrawn_count + expired.len() as u64))?;
+
+    // send swept principal to admin
     let msg = CosmosMsg::Bank(BankMsg::Send {
-        to_address: info.sender.to_string(),
+        to_address: admin_addr.to_string(),
         amount: vec![Coin {
             denom: "uosmo".to_string(),
-            amount: total_amount,
+            amount: swept_amount,
         }],
     });
 
     Ok(Response::new()
         .add_message(msg)
-        .add_attribute("method", "withdraw")
-        .add_attribute("total_amount", total_amount)
-        .add_attribute("sender", info.sender))
+        .add_attribute("method", "sweep_expired")
+        .add_attribute("swept", expired.len().to_string())
+        .add_attribute("amount", swept_amount))
+}
+
+pub fn try_fund_pool(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    // load admin address from storage
+    let admin_addr = ADMIN.load(deps.storage)?;
+
+    // verify sender is admin
+    if info.sender != admin_addr {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // validate uosmo sent
+    if info.funds.len() != 1 || info.funds[0].denom != "uosmo" {
+        return Err(ContractError::Std(StdError::generic_err("Invalid funds!")));
+    }
+
+    let reserve = RESERVE
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_add(info.funds[0].amount)
+        .map_err(StdError::from)?;
+    RESERVE.save(deps.storage, &reserve)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "fund_pool")
+        .add_attribute("amount", info.funds[0].amount)
+        .add_attribute("reserve", reserve))
+}
+
+pub fn try_fund_rewards(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    // load admin address from storage
+    let admin_addr = ADMIN.load(deps.storage)?;
+
+    // verify sender is admin
+    if info.sender != admin_addr {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // validate uosmo sent
+    if info.funds.len() != 1 || info.funds[0].denom != "uosmo" {
+        return Err(ContractError::Std(StdError::generic_err("Invalid funds!")));
+    }
+
+    let reward_pool = REWARD_POOL
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_add(info.funds[0].amount)
+        .map_err(StdError::from)?;
+    REWARD_POOL.save(deps.storage, &reward_pool)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "fund_rewards")
+        .add_attribute("amount", info.funds[0].amount)
+        .add_attribute("reward_pool", reward_pool))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // re-save lockdrops so legacy entries store `label: None`
+    let lockdrops = USER_LOCKDROP
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(u64, Lockdrop)>>>()?;
+
+    for (id, lockdrop) in &lockdrops {
+        USER_LOCKDROP.save(deps.storage, *id, lockdrop)?;
+    }
+
+    // rebuild per-owner counts from the stored lockdrops
+    let mut owner_counts: BTreeMap<Addr, u64> = BTreeMap::new();
+    for (_, lockdrop) in &lockdrops {
+        *owner_counts.entry(lockdrop.owner.clone()).or_default() += 1;
+    }
+    for (owner, count) in &owner_counts {
+        OWNER_LOCKDROP_COUNT.save(deps.storage, owner, count)?;
+    }
+
+    // legacy deployments predate the running total, rebuild it from the stored lockdrops
+    let total_locked = match TOTAL_LOCKED.may_load(deps.storage)? {
+        Some(total_locked) => total_locked,
+        None => {
+            let total_locked = lockdrops
+                .iter()
+                .try_fold(Uint128::zero(), |acc, (_, l)| acc.checked_add(l.amount))
+                .map_err(StdError::from)?;
+            TOTAL_LOCKED.save(deps.storage, &total_locked)?;
+            total_locked
+        }
+    };
+
+    // legacy instantiation required exactly the minimum seed
+    if SEED.may_load(deps.storage)?.is_none() {
+        SEED.save(deps.storage, &Coin::new(MINIMUM_SEED.into(), "uosmo"))?;
+    }
+
+    // anything held beyond principal and the reward pool backs bonuses
+    let reserve = match RESERVE.may_load(deps.storage)? {
+        Some(reserve) => reserve,
+        None => {
+            let balance = deps
+                .querier
+                .query_balance(&env.contract.address, "uosmo")?
+                .amount;
+            let reward_pool = REWARD_POOL.may_load(deps.storage)?.unwrap_or_default();
+            let reserve = balance
+                .saturating_sub(total_locked)
+                .saturating_sub(reward_pool);
+            RESERVE.save(deps.storage, &reserve)?;
+            reserve
+        }
+    };
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("lockdrops", lockdrops.len().to_string())
+        .add_attribute("total_locked", total_locked)
+        .add_attribute("reserve", reserve))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetNextLockdropId {} => to_binary(&query_next_id(deps)?),
         QueryMsg::GetLockdropInfo { id } => to_binary(&query_lockdrop_info(deps, id)?),
+        QueryMsg::PreviewWithdraw {
+            owner,
+            lockdrop_ids,
+        } => to_binary(&query_preview_withdraw(deps, env, owner, lockdrop_ids)?),
+        QueryMsg::PreviewWithdrawTotal { owner, ids } => {
+            to_binary(&query_preview_withdraw_total(deps, env, owner, ids)?)
+        }
+        QueryMsg::GetSolvencyRatio {} => to_binary(&query_solvency_ratio(deps)?),
+        QueryMsg::GetSeed {} => to_binary(&query_seed(deps, env)?),
+        QueryMsg::GetLockdropCounts {} => to_binary(&query_lockdrop_counts(deps)?),
+        QueryMsg::GetProjectedInsolvency {} => to_binary(&query_projected_insolvency(deps)?),
+        QueryMsg::GetLockdropsByStatus {
+            matured,
+            start_after,
+            limit,
+        } => to_binary(&query_lockdrops_by_status(
+            deps,
+            env,
+            matured,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::GetMaxSingleWithdraw {} => to_binary(&query_max_single_withdraw(deps)?),
+        QueryMsg::GetExpiringSoon {
+            within_seconds,
+            limit,
+        } => to_binary(&query_expiring_soon(deps, env, within_seconds, limit)?),
+        QueryMsg::GetTopProjectedPayouts { limit } => {
+            to_binary(&query_top_projected_payouts(deps, limit)?)
+        }
+        QueryMsg::GetProjectedPayout { owner } => {
+            to_binary(&query_projected_payout(deps, env, owner)?)
+        }
     }
 }
 
@@ -162,26 +714,330 @@ fn query_lockdrop_info(deps: Deps, id: u64) -> StdResult<Lockdrop> {
     Ok(lockdrop_info)
 }
 
+/// mirrors the eligibility and bonus rules of `try_withdraw` so the
+/// preview matches the real payout, ids are taken as given
+fn query_preview_withdraw(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    lockdrop_ids: Vec<u64>,
+) -> StdResult<PreviewResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+
+    let mut total = Uint128::zero();
+    let mut eligible_ids: Vec<u64> = vec![];
+
+    for id in lockdrop_ids {
+        let lockdrop = match USER_LOCKDROP.may_load(deps.storage, id)? {
+            Some(lockdrop) => lockdrop,
+            None => continue,
+        };
+
+        if lockdrop.owner == owner && env.block.time.seconds() >= lockdrop.unlock_time {
+            let bonus_amount = lockdrop
+                .amount
+                .checked_multiply_ratio(lockdrop.bonus_percent, 100_u64)
+                .map_err(|e| StdError::generic_err(e.to_string()))?;
+            total = total.checked_add(bonus_amount)?;
+            eligible_ids.push(id);
+        }
+    }
+
+    Ok(PreviewResponse {
+        total,
+        eligible_ids,
+    })
+}
+
+/// honest payout for a withdraw batch, counting each lockdrop id once
+fn query_preview_withdraw_total(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    mut ids: Vec<u64>,
+) -> StdResult<Uint128> {
+    ids.sort_unstable();
+    ids.dedup();
+
+    Ok(query_preview_withdraw(deps, env, owner, ids)?.total)
+}
+
+/// earliest unlock time at which cumulative payouts exceed principal, reserve and reward pool
+fn query_projected_insolvency(deps: Deps) -> StdResult<Option<u64>> {
+    let total_locked = TOTAL_LOCKED.may_load(deps.storage)?.unwrap_or_default();
+    let reserve = RESERVE.may_load(deps.storage)?.unwrap_or_default();
+    let reward_pool = REWARD_POOL.may_load(deps.storage)?.unwrap_or_default();
+    let available = total_locked
+        .checked_add(reserve)?
+        .checked_add(reward_pool)?;
+
+    let mut lockdrops = USER_LOCKDROP
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|v| Ok(v?.1))
+        .collect::<StdResult<Vec<Lockdrop>>>()?;
+    lockdrops.sort_by_key(|l| l.unlock_time);
+
+    let mut payouts = Uint128::zero();
+    for lockdrop in lockdrops {
+        let payout = lockdrop
+            .amount
+            .checked_multiply_ratio(lockdrop.bonus_percent, 100_u64)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+        payouts = payouts.checked_add(payout)?;
+        if payouts > available {
+            return Ok(Some(lockdrop.unlock_time));
+        }
+    }
+
+    Ok(None)
+}
+
+fn query_lockdrop_counts(deps: Deps) -> StdResult<LockdropCountsResponse> {
+    let created = LOCKDROP_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    let withdrawn = WITHDRAWN_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    Ok(LockdropCountsResponse {
+        created,
+        active: created.saturating_sub(withdrawn),
+        withdrawn,
+    })
+}
+
+fn query_lockdrops_by_status(
+    deps: Deps,
+    env: Env,
+    matured: bool,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<LockdropsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let now = env.block.time.seconds();
+
+    // matured lockdrops unlock at or before the current block time
+    let lockdrops = USER_LOCKDROP
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|v| {
+            v.as_ref()
+                .map_or(true, |(_, l)| (l.unlock_time <= now) == matured)
+        })
+        .take(limit)
+        .map(|v| Ok(v?.1))
+        .collect::<StdResult<Vec<Lockdrop>>>()?;
+
+    Ok(LockdropsResponse { lockdrops })
+}
+
+/// locked lockdrops that unlock within `within_seconds` of the current block time
+fn query_expiring_soon(
+    deps: Deps,
+    env: Env,
+    within_seconds: u64,
+    limit: Option<u32>,
+) -> StdResult<LockdropsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let now = env.block.time.seconds();
+    let until = now.saturating_add(within_seconds);
+
+    let lockdrops = USER_LOCKDROP
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|v| {
+            v.as_ref()
+                .map_or(true, |(_, l)| l.unlock_time > now && l.unlock_time <= until)
+        })
+        .take(limit)
+        .map(|v| Ok(v?.1))
+        .collect::<StdResult<Vec<Lockdrop>>>()?;
+
+    Ok(LockdropsResponse { lockdrops })
+}
+
+/// owners ranked by total projected payout, largest first
+fn query_top_projected_payouts(deps: Deps, limit: Option<u32>) -> StdResult<Vec<(Addr, Uint128)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_TOP_LIMIT) as usize;
+
+    let mut payouts: BTreeMap<Addr, Uint128> = BTreeMap::new();
+    for item in USER_LOCKDROP.range(deps.storage, None, None, Order::Ascending) {
+        let (_, lockdrop) = item?;
+        let payout = lockdrop
+            .amount
+            .checked_multiply_ratio(lockdrop.bonus_percent, 100_u64)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+        let total = payouts.entry(lockdrop.owner).or_default();
+        *total = total.checked_add(payout)?;
+    }
+
+    // stable sort keeps ties in address order
+    let mut ranking: Vec<(Addr, Uint128)> = payouts.into_iter().collect();
+    ranking.sort_by_key(|(_, payout)| std::cmp::Reverse(*payout));
+    ranking.truncate(limit);
+
+    Ok(ranking)
+}
+
+/// payout owed on all of an owner's lockdrops, split by maturity at the current block time
+fn query_projected_payout(
+    deps: Deps,
+    env: Env,
+    owner: String,
+) -> StdResult<ProjectedPayoutResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let now = env.block.time.seconds();
+
+    let mut matured = Uint128::zero();
+    let mut locked = Uint128::zero();
+    for item in USER_LOCKDROP.range(deps.storage, None, None, Order::Ascending) {
+        let (_, lockdrop) = item?;
+        if lockdrop.owner != owner {
+            continue;
+        }
+
+        let payout = lockdrop
+            .amount
+            .checked_multiply_ratio(lockdrop.bonus_percent, 100_u64)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+        if lockdrop.unlock_time <= now {
+            matured = matured.checked_add(payout)?;
+        } else {
+            locked = locked.checked_add(payout)?;
+        }
+    }
+
+    Ok(ProjectedPayoutResponse {
+        total: matured.checked_add(locked)?,
+        matured,
+        locked,
+    })
+}
+
+fn query_seed(deps: Deps, env: Env) -> StdResult<SeedResponse> {
+    let seed = SEED.load(deps.storage)?;
+    let balance = deps
+        .querier
+        .query_balance(env.contract.address, seed.denom.clone())?;
+    Ok(SeedResponse {
+        held: balance.amount >= seed.amount,
+        seed,
+    })
+}
+
+/// compare principal, reserve and reward pool against the payout owed on every lockdrop
+fn query_solvency_ratio(deps: Deps) -> StdResult<SolvencyRatioResponse> {
+    let user_principal_total = TOTAL_LOCKED.may_load(deps.storage)?.unwrap_or_default();
+    let reserve = RESERVE.may_load(deps.storage)?.unwrap_or_default();
+    let reward_pool = REWARD_POOL.may_load(deps.storage)?.unwrap_or_default();
+
+    let liabilities = USER_LOCKDROP
+        .range(deps.storage, None, None, Order::Ascending)
+        .try_fold(Uint128::zero(), |acc, item| -> StdResult<_> {
+            let (_, lockdrop) = item?;
+            let payout = lockdrop
+                .amount
+                .checked_multiply_ratio(lockdrop.bonus_percent, 100_u64)
+                .map_err(|e| StdError::generic_err(e.to_string()))?;
+            Ok(acc.checked_add(payout)?)
+        })?;
+
+    // fully solvent when nothing is owed
+    let ratio = if liabilities.is_zero() {
+        Decimal::one()
+    } else {
+        let assets = user_principal_total
+            .checked_add(reserve)?
+            .checked_add(reward_pool)?;
+        Decimal::from_ratio(assets, liabilities)
+    };
+
+    Ok(SolvencyRatioResponse {
+        user_principal_total,
+        reserve,
+        reward_pool,
+        liabilities,
+        ratio,
+    })
+}
+
+/// largest projected payout of any single lockdrop
+fn query_max_single_withdraw(deps: Deps) -> StdResult<Uint128> {
+    USER_LOCKDROP
+        .range(deps.storage, None, None, Order::Ascending)
+        .try_fold(Uint128::zero(), |max, item| -> StdResult<_> {
+            let (_, lockdrop) = item?;
+            let payout = lockdrop
+                .amount
+                .checked_multiply_ratio(lockdrop.bonus_percent, 100_u64)
+                .map_err(|e| StdError::generic_err(e.to_string()))?;
+            Ok(max.max(payout))
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary, Addr, Timestamp};
+    use cosmwasm_std::{coins, from_binary, Addr, Empty, Storage, Timestamp};
+    use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, MinterResponse};
+    use cw_multi_test::{App, Contract, ContractWrapper, Executor};
 
     #[test]
     #[should_panic(expected = "Invalid instantiation")]
     fn invalid_init() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
         let info = mock_info("creator", &coins(0, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
     }
 
+    #[test]
+    fn init_min_seed() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        // below the minimum seed
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: Some(Uint128::from(500_u64)),
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(499, "uosmo".to_string()));
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert_eq!(err.to_string(), "Generic error: Invalid instantiation");
+
+        // overpayment is accepted and recorded
+        let info = mock_info("creator", &coins(750, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetSeed {}).unwrap();
+        let value: SeedResponse = from_binary(&res).unwrap();
+        assert_eq!(value.seed, Coin::new(750, "uosmo"));
+    }
+
     #[test]
     fn deposit_withdraw_success() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -192,7 +1048,7 @@ mod tests {
 
         // user able to deposit uosmo
         let info = mock_info("alice", &coins(100, "uosmo"));
-        let msg = ExecuteMsg::Deposit {};
+        let msg = ExecuteMsg::Deposit { label: None };
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
         // verify deposit succeeded
@@ -239,7 +1095,16 @@ mod tests {
     fn deposit_failure() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -250,21 +1115,1755 @@ mod tests {
 
         // user able to deposit uosmo
         let info = mock_info("bob", &coins(10, "uosmo"));
-        let msg = ExecuteMsg::Deposit {};
+        let msg = ExecuteMsg::Deposit { label: None };
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     }
 
     #[test]
-    fn exploit() {
+    fn deposit_attributes() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let msg = ExecuteMsg::Deposit { label: None };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // verify lockdrop details are emitted
+        assert_eq!(res.attributes[3].value, "1");
+        assert_eq!(res.attributes[4].key, "lockdrop_id");
+        assert_eq!(res.attributes[4].value, "0");
+        assert_eq!(res.attributes[5].key, "unlock_time");
+        assert_eq!(
+            res.attributes[5].value,
+            mock_env()
+                .block
+                .time
+                .plus_seconds(LOCK_TIME)
+                .seconds()
+                .to_string()
+        );
+        assert_eq!(res.attributes[6].key, "bonus_pct");
+        assert_eq!(res.attributes[6].value, "5");
+    }
+
+    #[test]
+    fn deposit_unlock_time_matches_storage() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            lock_seconds: Some(3600),
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let msg = ExecuteMsg::Deposit { label: None };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res_query = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetLockdropInfo { id: 0_u64 },
+        )
+        .unwrap();
+        let lockdrop: Lockdrop = from_binary(&res_query).unwrap();
+
+        // unlock_time mirrors the stored unlock time
+        let unlock_time = res
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "unlock_time")
+            .unwrap();
+        assert_eq!(unlock_time.value, lockdrop.unlock_time.to_string());
+        assert_eq!(
+            lockdrop.unlock_time,
+            mock_env().block.time.plus_seconds(3600).seconds()
+        );
+    }
+
+    #[test]
+    fn instantiate_zero_lock_time() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: Some(0),
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+
+        // deposits would be withdrawable in the same block
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidLockTime {}));
+    }
+
+    #[test]
+    fn withdraw_skips_missing_ids() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let msg = ExecuteMsg::Deposit { label: None };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut tomorrow = mock_env();
+        tomorrow.block.time =
+            Timestamp::from_seconds(tomorrow.block.time.plus_seconds(LOCK_TIME).seconds());
+
+        // missing id is reported instead of failing the withdrawal
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            lockdrop_ids: vec![0_u64, 999_u64],
+        };
+        let res = execute(deps.as_mut(), tomorrow, info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "105");
+        assert_eq!(res.attributes[3].key, "skipped_ids");
+        assert_eq!(res.attributes[3].value, "999");
+    }
+
+    #[test]
+    fn withdraw_empty_ids() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            lockdrop_ids: vec![],
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::EmptyInput {}));
+    }
+
+    #[test]
+    fn deposit_withdraw_instant() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // instant tier carries no bonus
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let msg = ExecuteMsg::DepositInstant {};
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes[6].value, "0");
+
+        // withdrawable in the same block at 1:1
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            lockdrop_ids: vec![0_u64],
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "100");
+    }
+
+    #[test]
+    fn withdraw_all_matured() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // three locks for alice, one for bob
+        for (owner, amount) in [
+            ("alice", 100_u128),
+            ("alice", 200),
+            ("bob", 400),
+            ("alice", 300),
+        ] {
+            let info = mock_info(owner, &coins(amount, "uosmo"));
+            let _res = execute(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                ExecuteMsg::Deposit { label: None },
+            )
+            .unwrap();
+        }
+
+        // nothing matured yet
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::WithdrawAllMatured {};
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+        assert_eq!(err.to_string(), "Generic error: Nothing to withdraw!");
+
+        let mut tomorrow = mock_env();
+        tomorrow.block.time =
+            Timestamp::from_seconds(tomorrow.block.time.plus_seconds(LOCK_TIME).seconds());
+
+        // all of alice's locks paid out in one transfer
+        let res = execute(deps.as_mut(), tomorrow, info, msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(630, "uosmo"),
+            })
+        );
+
+        for id in [0_u64, 1, 3] {
+            assert!(!USER_LOCKDROP.has(deps.as_ref().storage, id));
+        }
+        assert!(USER_LOCKDROP.has(deps.as_ref().storage, 2));
+    }
+
+    #[test]
+    fn withdraw_bonus_overflow() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // whale deposits close to the maximum amount
+        let info = mock_info("whale", &coins(u128::MAX - 1, "uosmo"));
+        let msg = ExecuteMsg::Deposit { label: None };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut tomorrow = mock_env();
+        tomorrow.block.time =
+            Timestamp::from_seconds(tomorrow.block.time.plus_seconds(LOCK_TIME).seconds());
+
+        // bonus overflows, withdraw errors instead of panicking
+        let info = mock_info("whale", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            lockdrop_ids: vec![0_u64],
+        };
+        let err = execute(deps.as_mut(), tomorrow, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Std(_)));
+
+        // lockdrop is kept since the withdrawal failed
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetLockdropInfo { id: 0_u64 },
+        );
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn sweep_expired() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: Some(10 * 60 * 60),
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // alice and carol unlock after 24 hours, bob after 34 hours
+        for (owner, hours) in [("alice", 0_u64), ("carol", 0), ("bob", 10)] {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(hours * 60 * 60);
+            let info = mock_info(owner, &coins(100, "uosmo"));
+            let msg = ExecuteMsg::Deposit { label: None };
+            let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        }
+
+        // 40 hours in, alice and carol matured 16 hours ago and bob only 6
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(40 * 60 * 60);
+        let msg = ExecuteMsg::SweepExpired {
+            grace_seconds: 10 * 60 * 60,
+            limit: Some(1),
+        };
+
+        // only admin can sweep
+        let info = mock_info("alice", &[]);
+        let err = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // the grace cannot be shortened below the configured minimum
+        let short_msg = ExecuteMsg::SweepExpired {
+            grace_seconds: 10 * 60 * 60 - 1,
+            limit: None,
+        };
+        let info = mock_info("creator", &[]);
+        let err = execute(deps.as_mut(), env.clone(), info, short_msg).unwrap_err();
+        assert!(matches!(err, ContractError::GraceTooShort { min: 36_000 }));
+
+        // one lockdrop per call with a limit of one
+        let info = mock_info("creator", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".to_string(),
+                amount: coins(100, "uosmo"),
+            })
+        );
+        assert_eq!(res.attributes[1].value, "1");
+
+        assert!(!USER_LOCKDROP.has(deps.as_ref().storage, 0));
+        assert!(USER_LOCKDROP.has(deps.as_ref().storage, 1));
+        assert_eq!(
+            TOTAL_LOCKED.load(deps.as_ref().storage).unwrap(),
+            Uint128::from(200_u64)
+        );
+
+        let info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+        assert!(!USER_LOCKDROP.has(deps.as_ref().storage, 1));
+        assert!(USER_LOCKDROP.has(deps.as_ref().storage, 2));
+        assert_eq!(
+            TOTAL_LOCKED.load(deps.as_ref().storage).unwrap(),
+            Uint128::from(100_u64)
+        );
+
+        // the recently matured lockdrop is left alone
+        let info = mock_info("creator", &[]);
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(err.to_string(), "Generic error: Nothing to sweep!");
+    }
+
+    #[test]
+    fn withdraw_prefixed_event() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let instantiate_msg = |event_prefix: &str| InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: Some(event_prefix.to_string()),
+            min_sweep_grace: None,
+        };
+
+        // non-ascii prefixes are rejected
+        let info = mock_info("creator", &coins(1000, "uosmo"));
+        let msg = instantiate_msg("lockdrop\u{e9}");
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidEventPrefix {}));
+
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let msg = instantiate_msg("lockdrop5");
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let msg = ExecuteMsg::Deposit { label: None };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(LOCK_TIME);
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            lockdrop_ids: vec![0],
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // payout event is tagged with the lockdrop prefix
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "lockdrop5_withdraw");
+        assert_eq!(res.events[0].attributes, res.attributes);
+    }
+
+    #[test]
+    fn withdraw_accounting_drift() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let msg = ExecuteMsg::Deposit { label: None };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // corrupt the running total
+        TOTAL_LOCKED
+            .save(deps.as_mut().storage, &Uint128::from(99_u64))
+            .unwrap();
+
+        let mut tomorrow = mock_env();
+        tomorrow.block.time =
+            Timestamp::from_seconds(tomorrow.block.time.plus_seconds(LOCK_TIME).seconds());
+
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            lockdrop_ids: vec![0_u64],
+        };
+        let err = execute(deps.as_mut(), tomorrow, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::AccountingDrift {}));
+    }
+
+    #[test]
+    fn withdraw_guaranteed_principal_reconciles() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: Some(true),
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for _ in 0..2 {
+            let info = mock_info("alice", &coins(100, "uosmo"));
+            let msg = ExecuteMsg::Deposit { label: None };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let mut tomorrow = mock_env();
+        tomorrow.block.time =
+            Timestamp::from_seconds(tomorrow.block.time.plus_seconds(LOCK_TIME).seconds());
+
+        // a repeated id pays principal that never leaves storage
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            lockdrop_ids: vec![0_u64, 0_u64],
+        };
+        let err = execute(deps.as_mut(), tomorrow.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::AccountingDrift {}));
+
+        let msg = ExecuteMsg::Withdraw {
+            lockdrop_ids: vec![1_u64],
+        };
+        let res = execute(deps.as_mut(), tomorrow, info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "105");
+    }
+
+    #[test]
+    fn preview_matches_withdraw() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Deposit { label: None },
+        )
+        .unwrap();
+        let info = mock_info("alice", &coins(200, "uosmo"));
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Deposit { label: None },
+        )
+        .unwrap();
+        let info = mock_info("bob", &coins(300, "uosmo"));
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Deposit { label: None },
+        )
+        .unwrap();
+
+        let mut tomorrow = mock_env();
+        tomorrow.block.time =
+            Timestamp::from_seconds(tomorrow.block.time.plus_seconds(LOCK_TIME).seconds());
+
+        // still locked, nothing eligible
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PreviewWithdraw {
+                owner: "alice".to_string(),
+                lockdrop_ids: vec![0, 1],
+            },
+        )
+        .unwrap();
+        let preview: PreviewResponse = from_binary(&res).unwrap();
+        assert_eq!(preview.total, Uint128::zero());
+        assert!(preview.eligible_ids.is_empty());
+
+        let lockdrop_ids = vec![0_u64, 1, 2, 7];
+        let res = query(
+            deps.as_ref(),
+            tomorrow.clone(),
+            QueryMsg::PreviewWithdraw {
+                owner: "alice".to_string(),
+                lockdrop_ids: lockdrop_ids.clone(),
+            },
+        )
+        .unwrap();
+        let preview: PreviewResponse = from_binary(&res).unwrap();
+        assert_eq!(preview.total, Uint128::from(315_u64));
+        assert_eq!(preview.eligible_ids, vec![0, 1]);
+
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw { lockdrop_ids };
+        let res = execute(deps.as_mut(), tomorrow, info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, preview.total.to_string());
+    }
+
+    #[test]
+    fn preview_total_dedups_ids() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for amount in [100_u128, 200] {
+            let info = mock_info("alice", &coins(amount, "uosmo"));
+            let _res = execute(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                ExecuteMsg::Deposit { label: None },
+            )
+            .unwrap();
+        }
+
+        let mut tomorrow = mock_env();
+        tomorrow.block.time =
+            Timestamp::from_seconds(tomorrow.block.time.plus_seconds(LOCK_TIME).seconds());
+
+        // duplicates are only paid once
+        let ids = vec![1_u64, 0, 0, 1, 0, 1, 1, 9];
+        let res = query(
+            deps.as_ref(),
+            tomorrow.clone(),
+            QueryMsg::PreviewWithdrawTotal {
+                owner: "alice".to_string(),
+                ids: ids.clone(),
+            },
+        )
+        .unwrap();
+        let total: Uint128 = from_binary(&res).unwrap();
+        assert_eq!(total, Uint128::from(315_u64));
+
+        // the raw preview still reports the inflated amount
+        let res = query(
+            deps.as_ref(),
+            tomorrow,
+            QueryMsg::PreviewWithdraw {
+                owner: "alice".to_string(),
+                lockdrop_ids: ids,
+            },
+        )
+        .unwrap();
+        let preview: PreviewResponse = from_binary(&res).unwrap();
+        assert_eq!(preview.total, Uint128::from(1155_u64));
+    }
+
+    #[test]
+    fn max_single_withdraw() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let max_single_withdraw = |deps: Deps| -> Uint128 {
+            let res = query(deps, mock_env(), QueryMsg::GetMaxSingleWithdraw {}).unwrap();
+            from_binary(&res).unwrap()
+        };
+        assert_eq!(max_single_withdraw(deps.as_ref()), Uint128::zero());
+
+        // a larger instant lockdrop can still pay less than a bonus one
+        let info = mock_info("alice", &coins(400, "uosmo"));
+        let msg = ExecuteMsg::Deposit { label: None };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("bob", &coins(410, "uosmo"));
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::DepositInstant {},
+        )
+        .unwrap();
+        let info = mock_info("carol", &coins(100, "uosmo"));
+        let msg = ExecuteMsg::Deposit { label: None };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        assert_eq!(max_single_withdraw(deps.as_ref()), Uint128::from(420_u64));
+    }
+
+    #[test]
+    fn bonus_draws_from_reserve() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // only admin can fund the reserve
+        let info = mock_info("alice", &coins(500, "uosmo"));
+        let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::FundPool {}).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let info = mock_info("creator", &coins(500, "uosmo"));
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::FundPool {}).unwrap();
+        assert_eq!(res.attributes[2].value, "1500");
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Deposit { label: None },
+        )
+        .unwrap();
+        let info = mock_info("bob", &coins(200, "uosmo"));
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Deposit { label: None },
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetSolvencyRatio {}).unwrap();
+        let value: SolvencyRatioResponse = from_binary(&res).unwrap();
+        assert_eq!(value.user_principal_total, Uint128::from(300_u64));
+        assert_eq!(value.reserve, Uint128::from(1500_u64));
+        assert_eq!(value.liabilities, Uint128::from(315_u64));
+        assert_eq!(value.ratio, Decimal::from_ratio(1800_u64, 315_u64));
+
+        let mut tomorrow = mock_env();
+        tomorrow.block.time =
+            Timestamp::from_seconds(tomorrow.block.time.plus_seconds(LOCK_TIME).seconds());
+
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            lockdrop_ids: vec![0_u64],
+        };
+        let res = execute(deps.as_mut(), tomorrow, info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "105");
+
+        // bonus came out of the reserve, principal matches remaining deposits
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetSolvencyRatio {}).unwrap();
+        let value: SolvencyRatioResponse = from_binary(&res).unwrap();
+        assert_eq!(value.user_principal_total, Uint128::from(200_u64));
+        assert_eq!(value.reserve, Uint128::from(1495_u64));
+        assert_eq!(value.liabilities, Uint128::from(210_u64));
+
+        // the reward pool also backs payouts
+        let info = mock_info("creator", &coins(100, "uosmo"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::FundRewards {}).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetSolvencyRatio {}).unwrap();
+        let value: SolvencyRatioResponse = from_binary(&res).unwrap();
+        assert_eq!(value.reward_pool, Uint128::from(100_u64));
+        assert_eq!(value.ratio, Decimal::from_ratio(1795_u64, 210_u64));
+    }
+
+    #[test]
+    fn guarantee_principal_caps_bonus() {
+        for guarantee_principal in [false, true] {
+            let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+            // reserve can only back 2 of the 5 bonus
+            let msg = InstantiateMsg {
+                lock_seconds: None,
+                min_seed: Some(Uint128::from(2_u64)),
+                reward_token: None,
+                max_lockdrops_per_owner: None,
+                guarantee_principal: Some(guarantee_principal),
+                split_payout: None,
+                event_prefix: None,
+                min_sweep_grace: None,
+            };
+            let info = mock_info("creator", &coins(2, "uosmo".to_string()));
+            let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            for user in ["alice", "bob"] {
+                let info = mock_info(user, &coins(100, "uosmo"));
+                let _res = execute(
+                    deps.as_mut(),
+                    mock_env(),
+                    info,
+                    ExecuteMsg::Deposit { label: None },
+                )
+                .unwrap();
+            }
+
+            let mut tomorrow = mock_env();
+            tomorrow.block.time =
+                Timestamp::from_seconds(tomorrow.block.time.plus_seconds(LOCK_TIME).seconds());
+
+            let info = mock_info("alice", &[]);
+            let msg = ExecuteMsg::Withdraw {
+                lockdrop_ids: vec![0_u64],
+            };
+            let res = execute(deps.as_mut(), tomorrow, info, msg).unwrap();
+
+            let res_query =
+                query(deps.as_ref(), mock_env(), QueryMsg::GetSolvencyRatio {}).unwrap();
+            let value: SolvencyRatioResponse = from_binary(&res_query).unwrap();
+            assert_eq!(value.reserve, Uint128::zero());
+
+            if guarantee_principal {
+                // full principal, bonus reduced to what the reserve held
+                assert_eq!(
+                    res.messages[0].msg,
+                    CosmosMsg::Bank(BankMsg::Send {
+                        to_address: "alice".to_string(),
+                        amount: coins(102, "uosmo"),
+                    })
+                );
+                assert_eq!(res.attributes[3].key, "bonus_shortfall");
+                assert_eq!(res.attributes[3].value, "3");
+                assert_eq!(value.user_principal_total, Uint128::from(100_u64));
+            } else {
+                // shortfall is taken from bob's principal
+                assert_eq!(
+                    res.messages[0].msg,
+                    CosmosMsg::Bank(BankMsg::Send {
+                        to_address: "alice".to_string(),
+                        amount: coins(105, "uosmo"),
+                    })
+                );
+                assert_eq!(value.user_principal_total, Uint128::from(97_u64));
+            }
+        }
+    }
+
+    #[test]
+    fn split_payout() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: Some(true),
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(200, "uosmo"));
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Deposit { label: None },
+        )
+        .unwrap();
+
+        let mut tomorrow = mock_env();
+        tomorrow.block.time =
+            Timestamp::from_seconds(tomorrow.block.time.plus_seconds(LOCK_TIME).seconds());
+
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            lockdrop_ids: vec![0_u64],
+        };
+        let res = execute(deps.as_mut(), tomorrow, info, msg).unwrap();
+
+        // principal and bonus are separate sends
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(200, "uosmo"),
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(10, "uosmo"),
+            })
+        );
+
+        // instant lockdrops have no bonus to send
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::DepositInstant {},
+        )
+        .unwrap();
+
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            lockdrop_ids: vec![1_u64],
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+    }
+
+    #[test]
+    fn seed_status() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "uosmo"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetSeed {}).unwrap();
+        let value: SeedResponse = from_binary(&res).unwrap();
+        assert_eq!(value.seed, Coin::new(1000, "uosmo"));
+        assert!(value.held);
+
+        // contract balance drops below the seed
+        deps.querier
+            .update_balance(mock_env().contract.address, coins(999, "uosmo"));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetSeed {}).unwrap();
+        let value: SeedResponse = from_binary(&res).unwrap();
+        assert_eq!(value.seed, Coin::new(1000, "uosmo"));
+        assert!(!value.held);
+    }
+
+    #[test]
+    fn projected_insolvency() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let query_insolvency = |deps: Deps| -> Option<u64> {
+            let res = query(deps, mock_env(), QueryMsg::GetProjectedInsolvency {}).unwrap();
+            from_binary(&res).unwrap()
+        };
+
+        // reserve covers the bonus
+        let info = mock_info("alice", &coins(20_000, "uosmo"));
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Deposit { label: None },
+        )
+        .unwrap();
+        assert_eq!(query_insolvency(deps.as_ref()), None);
+
+        // second lock pushes payouts past principal plus reserve
+        let mut later = mock_env();
+        later.block.time = later.block.time.plus_seconds(3600);
+        let info = mock_info("bob", &coins(20_000, "uosmo"));
+        let _res = execute(
+            deps.as_mut(),
+            later.clone(),
+            info,
+            ExecuteMsg::Deposit { label: None },
+        )
+        .unwrap();
+        assert_eq!(
+            query_insolvency(deps.as_ref()),
+            Some(later.block.time.plus_seconds(LOCK_TIME).seconds())
+        );
+
+        // funding the reward pool covers the remaining bonus
+        let info = mock_info("creator", &coins(1_000, "uosmo"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::FundRewards {}).unwrap();
+        assert_eq!(query_insolvency(deps.as_ref()), None);
+    }
+
+    #[test]
+    fn deposit_label() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let msg = ExecuteMsg::Deposit {
+            label: Some("rainy day".to_string()),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetLockdropInfo { id: 0_u64 },
+        )
+        .unwrap();
+        let value: Lockdrop = from_binary(&res).unwrap();
+        assert_eq!(value.label, Some("rainy day".to_string()));
+
+        // labels are listed too
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetLockdropsByStatus {
+                matured: false,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: LockdropsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.lockdrops[0].label, Some("rainy day".to_string()));
+
+        // over-long labels are rejected
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let msg = ExecuteMsg::Deposit {
+            label: Some("a".repeat(65)),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::LabelTooLong {}));
+    }
+
+    #[test]
+    fn migrate_backfills_label() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        // lockdrop stored before `label` existed
+        deps.storage.set(
+            &USER_LOCKDROP.key(0_u64),
+            br#"{"id":0,"owner":"alice","amount":"100","unlock_time":1,"bonus_percent":105}"#,
+        );
+
+        let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        let value = USER_LOCKDROP.load(&deps.storage, 0_u64).unwrap();
+        assert_eq!(value.owner, "alice");
+        assert_eq!(value.label, None);
+        assert!(
+            String::from_utf8(deps.storage.get(&USER_LOCKDROP.key(0_u64)).unwrap())
+                .unwrap()
+                .contains(r#""label":null"#)
+        );
+    }
+
+    #[test]
+    fn migrate_legacy_totals() {
+        let mut deps = mock_dependencies_with_balance(&coins(1_300, "uosmo"));
+
+        // legacy state holds lockdrops but no totals, reserve or seed
+        deps.storage.set(
+            &USER_LOCKDROP.key(0_u64),
+            br#"{"id":0,"owner":"alice","amount":"100","unlock_time":1}"#,
+        );
+        deps.storage.set(
+            &USER_LOCKDROP.key(1_u64),
+            br#"{"id":1,"owner":"bob","amount":"200","unlock_time":1}"#,
+        );
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(res.attributes[2].value, "300");
+        assert_eq!(res.attributes[3].value, "1000");
+        assert_eq!(SEED.load(&deps.storage).unwrap(), Coin::new(1_000, "uosmo"));
+
+        // the migrated state pays principal plus bonus
+        let msg = ExecuteMsg::Withdraw {
+            lockdrop_ids: vec![0_u64],
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(105, "uosmo"),
+            })
+        );
+        assert_eq!(
+            TOTAL_LOCKED.load(&deps.storage).unwrap(),
+            Uint128::from(200_u64)
+        );
+        assert_eq!(RESERVE.load(&deps.storage).unwrap(), Uint128::from(995_u64));
+
+        // a second migration keeps the running totals
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(res.attributes[2].value, "200");
+        assert_eq!(res.attributes[3].value, "995");
+    }
+
+    #[test]
+    fn lockdrop_counts() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let query_counts = |deps: Deps| -> LockdropCountsResponse {
+            let res = query(deps, mock_env(), QueryMsg::GetLockdropCounts {}).unwrap();
+            from_binary(&res).unwrap()
+        };
+
+        for amount in [100_u128, 200, 300] {
+            let info = mock_info("alice", &coins(amount, "uosmo"));
+            let _res = execute(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                ExecuteMsg::Deposit { label: None },
+            )
+            .unwrap();
+        }
+        assert_eq!(
+            query_counts(deps.as_ref()),
+            LockdropCountsResponse {
+                created: 3,
+                active: 3,
+                withdrawn: 0,
+            }
+        );
+
+        let mut tomorrow = mock_env();
+        tomorrow.block.time =
+            Timestamp::from_seconds(tomorrow.block.time.plus_seconds(LOCK_TIME).seconds());
+
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            lockdrop_ids: vec![0_u64, 2_u64],
+        };
+        let _res = execute(deps.as_mut(), tomorrow, info, msg).unwrap();
+        assert_eq!(
+            query_counts(deps.as_ref()),
+            LockdropCountsResponse {
+                created: 3,
+                active: 1,
+                withdrawn: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn owner_lockdrop_limit() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: Some(2),
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // alice fills her slots
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        for _ in 0..2 {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                ExecuteMsg::DepositInstant {},
+            )
+            .unwrap();
+        }
+
+        // one more is rejected
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Deposit { label: None },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::OwnerLockdropLimit {}));
+
+        // the cap is per owner
+        let bob = mock_info("bob", &coins(100, "uosmo"));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            bob,
+            ExecuteMsg::Deposit { label: None },
+        )
+        .unwrap();
+
+        // withdrawing frees a slot
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::Withdraw {
+                lockdrop_ids: vec![0],
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Deposit { label: None },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn owner_lockdrop_limit_withdraw_all() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: Some(3),
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // deposit up to the cap across both tiers
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        for msg in [
+            ExecuteMsg::Deposit { label: None },
+            ExecuteMsg::DepositInstant {},
+            ExecuteMsg::Deposit { label: None },
+        ] {
+            execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        }
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::DepositInstant {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::OwnerLockdropLimit {}));
+
+        let mut tomorrow = mock_env();
+        tomorrow.block.time =
+            Timestamp::from_seconds(tomorrow.block.time.plus_seconds(LOCK_TIME).seconds());
+
+        // sweeping matured locks frees every slot
+        execute(
+            deps.as_mut(),
+            tomorrow.clone(),
+            mock_info("alice", &[]),
+            ExecuteMsg::WithdrawAllMatured {},
+        )
+        .unwrap();
+        assert_eq!(
+            OWNER_LOCKDROP_COUNT
+                .load(deps.as_ref().storage, &Addr::unchecked("alice"))
+                .unwrap(),
+            0
+        );
+        for _ in 0..3 {
+            execute(
+                deps.as_mut(),
+                tomorrow.clone(),
+                info.clone(),
+                ExecuteMsg::DepositInstant {},
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn lockdrops_by_status() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // one lock matures now, the other in 24 hours
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Deposit { label: None },
+        )
+        .unwrap();
+        let info = mock_info("bob", &coins(200, "uosmo"));
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::DepositInstant {},
+        )
+        .unwrap();
+
+        let query_status = |deps: Deps, matured: bool| -> Vec<u64> {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::GetLockdropsByStatus {
+                    matured,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+            let value: LockdropsResponse = from_binary(&res).unwrap();
+            value.lockdrops.iter().map(|l| l.id).collect()
+        };
+
+        assert_eq!(query_status(deps.as_ref(), true), vec![1]);
+        assert_eq!(query_status(deps.as_ref(), false), vec![0]);
+    }
+
+    #[test]
+    fn top_projected_payouts() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // alice 105 + 210, bob 420 instant, carol 400 locked
+        for (owner, amount) in [("alice", 100_u128), ("carol", 400), ("alice", 200)] {
+            let info = mock_info(owner, &coins(amount, "uosmo"));
+            let msg = ExecuteMsg::Deposit { label: None };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+        let info = mock_info("bob", &coins(420, "uosmo"));
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::DepositInstant {},
+        )
+        .unwrap();
+
+        let top = |deps: Deps, limit: Option<u32>| -> Vec<(Addr, Uint128)> {
+            let msg = QueryMsg::GetTopProjectedPayouts { limit };
+            let res = query(deps, mock_env(), msg).unwrap();
+            from_binary(&res).unwrap()
+        };
+
+        // bob and carol tie at 420, ordered by address
+        assert_eq!(
+            top(deps.as_ref(), None),
+            vec![
+                (Addr::unchecked("bob"), Uint128::from(420_u64)),
+                (Addr::unchecked("carol"), Uint128::from(420_u64)),
+                (Addr::unchecked("alice"), Uint128::from(315_u64)),
+            ]
+        );
+        assert_eq!(top(deps.as_ref(), Some(1)).len(), 1);
+    }
+
+    #[test]
+    fn projected_payout() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // alice has a matured instant lock at 100% and a locked one at 105%, bob's is ignored
+        let info = mock_info("alice", &coins(200, "uosmo"));
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::DepositInstant {},
+        )
+        .unwrap();
+        for (owner, amount) in [("alice", 100_u128), ("bob", 300)] {
+            let info = mock_info(owner, &coins(amount, "uosmo"));
+            let msg = ExecuteMsg::Deposit { label: None };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let msg = QueryMsg::GetProjectedPayout {
+            owner: "alice".to_string(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ProjectedPayoutResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value,
+            ProjectedPayoutResponse {
+                total: Uint128::from(305_u64),
+                matured: Uint128::from(200_u64),
+                locked: Uint128::from(105_u64),
+            }
+        );
+    }
+
+    #[test]
+    fn expiring_soon() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // instant lock, then locks created 0, 6 and 12 hours after the start
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::DepositInstant {},
+        )
+        .unwrap();
+        for hours in [0_u64, 6, 12] {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(hours * 60 * 60);
+            let info = mock_info("alice", &coins(100, "uosmo"));
+            let msg = ExecuteMsg::Deposit { label: None };
+            let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        }
+
+        // 20 hours in, the next 11 hours cover locks unlocking at 24 and 30 hours
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(20 * 60 * 60);
+        let msg = QueryMsg::GetExpiringSoon {
+            within_seconds: 11 * 60 * 60,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), env, msg).unwrap();
+        let value: LockdropsResponse = from_binary(&res).unwrap();
+        let ids: Vec<u64> = value.lockdrops.iter().map(|l| l.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn withdraw_mints_reward_bonus() {
+        fn cw20_contract() -> Box<dyn Contract<Empty>> {
+            let contract = ContractWrapper::new(
+                cw20_base::contract::execute,
+                cw20_base::contract::instantiate,
+                cw20_base::contract::query,
+            );
+            Box::new(contract)
+        }
+
+        fn ctf_contract() -> Box<dyn Contract<Empty>> {
+            let contract = ContractWrapper::new(
+                crate::contract::execute,
+                crate::contract::instantiate,
+                crate::contract::query,
+            );
+            Box::new(contract)
+        }
+
+        let mut app = App::new(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked("creator"), coins(1000, "uosmo"))
+                .unwrap();
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked("alice"), coins(100, "uosmo"))
+                .unwrap();
+        });
+        let cw20_id = app.store_code(cw20_contract());
+        let ctf_id = app.store_code(ctf_contract());
+
+        // ctf contract is instantiated next, so it gets the following address
+        let msg = cw20_base::msg::InstantiateMsg {
+            name: "Reward".to_string(),
+            symbol: "RWD".to_string(),
+            decimals: 6,
+            initial_balances: vec![],
+            mint: Some(MinterResponse {
+                minter: "contract1".to_string(),
+                cap: None,
+            }),
+            marketing: None,
+        };
+        let reward_addr = app
+            .instantiate_contract(
+                cw20_id,
+                Addr::unchecked("creator"),
+                &msg,
+                &[],
+                "reward",
+                None,
+            )
+            .unwrap();
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: Some(reward_addr.to_string()),
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let ctf_addr = app
+            .instantiate_contract(
+                ctf_id,
+                Addr::unchecked("creator"),
+                &msg,
+                &coins(1000, "uosmo"),
+                "ctf5",
+                None,
+            )
+            .unwrap();
+        assert_eq!(ctf_addr, "contract1");
+
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            ctf_addr.clone(),
+            &ExecuteMsg::Deposit { label: None },
+            &coins(100, "uosmo"),
+        )
+        .unwrap();
+
+        app.update_block(|block| block.time = block.time.plus_seconds(LOCK_TIME));
+
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            ctf_addr,
+            &ExecuteMsg::Withdraw {
+                lockdrop_ids: vec![0_u64],
+            },
+            &[],
+        )
+        .unwrap();
+
+        // principal is returned in uosmo and the bonus minted
+        let balance = app.wrap().query_balance("alice", "uosmo").unwrap();
+        assert_eq!(balance.amount, Uint128::from(100_u64));
+
+        let res: Cw20BalanceResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &reward_addr,
+                &Cw20QueryMsg::Balance {
+                    address: "alice".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(res.balance, Uint128::from(5_u64));
+    }
+
+    #[test]
+    fn reward_pool_backs_bonus() {
+        fn ctf_contract() -> Box<dyn Contract<Empty>> {
+            let contract = ContractWrapper::new(
+                crate::contract::execute,
+                crate::contract::instantiate,
+                crate::contract::query,
+            );
+            Box::new(contract)
+        }
+
+        let mut app = App::new(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked("creator"), coins(1005, "uosmo"))
+                .unwrap();
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked("alice"), coins(200, "uosmo"))
+                .unwrap();
+        });
+        let ctf_id = app.store_code(ctf_contract());
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
+        let ctf_addr = app
+            .instantiate_contract(
+                ctf_id,
+                Addr::unchecked("creator"),
+                &msg,
+                &coins(1000, "uosmo"),
+                "ctf5",
+                None,
+            )
+            .unwrap();
+
+        // only admin can fund rewards
+        let err = app
+            .execute_contract(
+                Addr::unchecked("alice"),
+                ctf_addr.clone(),
+                &ExecuteMsg::FundRewards {},
+                &coins(5, "uosmo"),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized {}
+        ));
+
+        // fund exactly one bonus
+        app.execute_contract(
+            Addr::unchecked("creator"),
+            ctf_addr.clone(),
+            &ExecuteMsg::FundRewards {},
+            &coins(5, "uosmo"),
+        )
+        .unwrap();
+
+        for _ in 0..2 {
+            app.execute_contract(
+                Addr::unchecked("alice"),
+                ctf_addr.clone(),
+                &ExecuteMsg::Deposit { label: None },
+                &coins(100, "uosmo"),
+            )
+            .unwrap();
+        }
+
+        app.update_block(|block| block.time = block.time.plus_seconds(LOCK_TIME));
+
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            ctf_addr.clone(),
+            &ExecuteMsg::Withdraw {
+                lockdrop_ids: vec![0_u64],
+            },
+            &[],
+        )
+        .unwrap();
+        let balance = app.wrap().query_balance("alice", "uosmo").unwrap();
+        assert_eq!(balance.amount, Uint128::from(105_u64));
+
+        // reward pool is drained, next bonus is not backed
+        let err = app
+            .execute_contract(
+                Addr::unchecked("alice"),
+                ctf_addr,
+                &ExecuteMsg::Withdraw {
+                    lockdrop_ids: vec![1_u64],
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InsufficientRewards {}
+        ));
+        let balance = app.wrap().query_balance("alice", "uosmo").unwrap();
+        assert_eq!(balance.amount, Uint128::from(105_u64));
+    }
+
+    #[test]
+    fn exploit() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            lock_seconds: None,
+            min_seed: None,
+            reward_token: None,
+            max_lockdrops_per_owner: None,
+            guarantee_principal: None,
+            split_payout: None,
+            event_prefix: None,
+            min_sweep_grace: None,
+        };
         let info = mock_info("creator", &coins(1000, "uosmo".to_string()));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
         // hacker deposits uosmo
         let info = mock_info("hacker", &coins(100, "uosmo"));
-        let msg = ExecuteMsg::Deposit {};
+        let msg = ExecuteMsg::Deposit { label: None };
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
         // hacker waits until lockdrop unlocked
@@ -272,7 +2871,7 @@ mod tests {
         tomorrow.block.time =
             Timestamp::from_seconds(tomorrow.block.time.plus_seconds(LOCK_TIME).seconds());
 
-        // hacker sends a vector of same lockdrop ids. 
+        // hacker sends a vector of same lockdrop ids.
         // since `.remove` does not revert an error if item doesn't exists (ie. remove non-existent items), this vulnerable implementation allows the hacker to steal user funds in the contract
         let info = mock_info("hacker", &[]);
         let msg = ExecuteMsg::Withdraw {
@@ -9,6 +9,33 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Invalid lock time")]
+    InvalidLockTime {},
+
+    #[error("Accounting drift detected")]
+    AccountingDrift {},
+
+    #[error("Insufficient funds for payout")]
+    InsufficientFunds {},
+
+    #[error("Label too long")]
+    LabelTooLong {},
+
+    #[error("Owner lockdrop limit reached")]
+    OwnerLockdropLimit {},
+
+    #[error("Insufficient rewards")]
+    InsufficientRewards {},
+
+    #[error("Empty input")]
+    EmptyInput {},
+
+    #[error("Grace period below the minimum of {min} seconds")]
+    GraceTooShort { min: u64 },
+
+    #[error("Invalid event prefix")]
+    InvalidEventPrefix {},
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
     // Add any other custom errors you like here.
@@ -1,9 +1,10 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{to_binary, Addr, CosmosMsg, StdResult, WasmMsg};
+use cosmwasm_std::{to_binary, Addr, CosmosMsg, Event, Response, StdResult, WasmMsg};
 
 use crate::msg::ExecuteMsg;
+use crate::ContractError;
 
 // CwTemplateContract is a wrapper around Addr that provides a lot of helpers
 // for working with this.
@@ -25,3 +26,36 @@ impl CwTemplateContract {
         .into())
     }
 }
+
+/// longest accepted event prefix
+pub const MAX_EVENT_PREFIX_LENGTH: usize = 32;
+
+/// event prefixes must be non-empty ascii letters, digits, `_` or `-`
+pub fn validate_event_prefix(event_prefix: &str) -> Result<(), ContractError> {
+    let valid = !event_prefix.is_empty()
+        && event_prefix.len() <= MAX_EVENT_PREFIX_LENGTH
+        && event_prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(ContractError::InvalidEventPrefix {});
+    }
+    Ok(())
+}
+
+/// lockdrop actions also emit `<prefix>_<method>`, e.g. `lockdrop5_withdraw`, with the response attributes
+pub fn add_prefixed_event(res: Response, event_prefix: &str) -> Response {
+    if event_prefix.is_empty() {
+        return res;
+    }
+
+    let method = res
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "method")
+        .map_or("", |attr| attr.value.as_str());
+    let event =
+        Event::new(format!("{}_{}", event_prefix, method)).add_attributes(res.attributes.clone());
+
+    res.add_event(event)
+}
@@ -1,14 +1,41 @@
+use cosmwasm_std::{Coin, Decimal, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::state::Lockdrop;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub lock_seconds: Option<u64>,
+    pub min_seed: Option<Uint128>,
+    pub reward_token: Option<String>,
+    pub max_lockdrops_per_owner: Option<u64>,
+    pub guarantee_principal: Option<bool>,
+    pub split_payout: Option<bool>,
+    pub event_prefix: Option<String>,
+    pub min_sweep_grace: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct InstantiateMsg {}
+pub struct MigrateMsg {}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    Deposit {},
-    Withdraw { lockdrop_ids: Vec<u64> },
+    Deposit {
+        label: Option<String>,
+    },
+    DepositInstant {},
+    Withdraw {
+        lockdrop_ids: Vec<u64>,
+    },
+    WithdrawAllMatured {},
+    FundPool {},
+    FundRewards {},
+    SweepExpired {
+        grace_seconds: u64,
+        limit: Option<u32>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -21,5 +48,81 @@ pub struct NextLockdropId {
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     GetNextLockdropId {},
-    GetLockdropInfo { id: u64 },
+    GetLockdropInfo {
+        id: u64,
+    },
+    PreviewWithdraw {
+        owner: String,
+        lockdrop_ids: Vec<u64>,
+    },
+    PreviewWithdrawTotal {
+        owner: String,
+        ids: Vec<u64>,
+    },
+    GetSolvencyRatio {},
+    GetSeed {},
+    GetLockdropCounts {},
+    GetProjectedInsolvency {},
+    GetLockdropsByStatus {
+        matured: bool,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    GetMaxSingleWithdraw {},
+    GetExpiringSoon {
+        within_seconds: u64,
+        limit: Option<u32>,
+    },
+    GetTopProjectedPayouts {
+        limit: Option<u32>,
+    },
+    GetProjectedPayout {
+        owner: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct LockdropCountsResponse {
+    pub created: u64,
+    pub active: u64,
+    pub withdrawn: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct LockdropsResponse {
+    pub lockdrops: Vec<Lockdrop>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SeedResponse {
+    pub seed: Coin,
+    pub held: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SolvencyRatioResponse {
+    pub user_principal_total: Uint128,
+    pub reserve: Uint128,
+    pub reward_pool: Uint128,
+    pub liabilities: Uint128,
+    pub ratio: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PreviewResponse {
+    pub total: Uint128,
+    pub eligible_ids: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProjectedPayoutResponse {
+    pub total: Uint128,
+    pub matured: Uint128,
+    pub locked: Uint128,
 }
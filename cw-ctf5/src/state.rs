@@ -1,4 +1,4 @@
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Coin, Uint128};
 use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -13,10 +13,63 @@ pub struct Lockdrop {
     pub amount: Uint128,
     /// unlock time for this specific lockdrop
     pub unlock_time: u64,
+    /// payout percentage applied on withdrawal
+    #[serde(default = "default_bonus_percent")]
+    pub bonus_percent: u64,
+    /// optional client label, at most 64 bytes
+    #[serde(default)]
+    pub label: Option<String>,
 }
 
+/// lockdrops created before tiers existed use the standard 105% payout
+fn default_bonus_percent() -> u64 {
+    105
+}
+
+/// store admin address
+pub const ADMIN: Item<Addr> = Item::new("admin_addr");
+
+/// funds provided at instantiation
+pub const SEED: Item<Coin> = Item::new("seed");
+
+/// locking time for new lockdrops, in seconds
+pub const LOCK_SECONDS: Item<u64> = Item::new("lock_seconds");
+
+/// running total of principal held in lockdrops
+pub const TOTAL_LOCKED: Item<Uint128> = Item::new("total_locked");
+
+/// admin provided funds backing lockdrop bonuses
+pub const RESERVE: Item<Uint128> = Item::new("reserve");
+
+/// admin provided funds strictly backing bonuses, unchecked until first funded
+pub const REWARD_POOL: Item<Uint128> = Item::new("reward_pool");
+
+/// cw20 token minted for bonuses, bonuses are paid in uosmo when unset
+pub const REWARD_TOKEN: Item<Addr> = Item::new("reward_token");
+
 /// increment as lockdrop identifier
 pub const LOCKDROP_COUNT: Item<u64> = Item::new("lockdrop_count");
 
+/// number of lockdrops removed by withdrawals
+pub const WITHDRAWN_COUNT: Item<u64> = Item::new("withdrawn_count");
+
+/// maximum active lockdrops per owner, zero means unlimited
+pub const MAX_LOCKDROPS_PER_OWNER: Item<u64> = Item::new("max_lockdrops_per_owner");
+
+/// cap bonuses at the funded pool so withdrawals never touch other users' principal
+pub const GUARANTEE_PRINCIPAL: Item<bool> = Item::new("guarantee_principal");
+
+/// pay principal and bonus as separate bank sends so indexers can tell them apart
+pub const SPLIT_PAYOUT: Item<bool> = Item::new("split_payout");
+
+/// number of active lockdrops per owner
+pub const OWNER_LOCKDROP_COUNT: Map<&Addr, u64> = Map::new("owner_lockdrop_count");
+
 /// lockdrop id to lockdrop struct
 pub const USER_LOCKDROP: Map<u64, Lockdrop> = Map::new("user_lockdrop");
+
+/// shortest grace period the admin may sweep unclaimed lockdrops with, in seconds
+pub const MIN_SWEEP_GRACE: Item<u64> = Item::new("min_sweep_grace");
+
+/// prefix for custom event types, empty when not configured
+pub const EVENT_PREFIX: Item<String> = Item::new("event_prefix");
@@ -1,24 +1,104 @@
+use std::collections::BTreeMap;
 use std::vec;
 
 use crate::error::ContractError;
-use crate::msg::{AllDonations, ExecuteMsg, InstantiateMsg, NextDonationId, QueryMsg};
-use crate::state::{Donation, ADMIN, DONATIONS, DONATION_COUNT};
+use crate::helpers::{add_prefixed_event, validate_event_prefix};
+use crate::msg::{
+    AdminWithdrawableResponse, AllDonations, CanWithdrawAllResponse, ConfigResponse, ExecuteMsg,
+    InstantiateMsg, MigrateMsg, NextDonationId, QueryMsg, StatsResponse,
+};
+#[cfg(feature = "debug")]
+use crate::msg::{DebugDumpResponse, DebugEntry};
+use crate::state::{
+    default_denom, Donation, PendingWithdrawal, ACCEPTED_DENOMS, ADMIN, CAMPAIGN_END,
+    CAMPAIGN_START, DENOM_DONATED, DENOM_DONATION_COUNT, DENOM_MIN_DONATIONS, DONATIONS,
+    DONATIONS_BY_USER, DONATION_COUNT, DONATOR_COUNT, EVENT_PREFIX, INSTANTIATED_AT, LAST_WITHDRAW,
+    MIN_DONATION, PENDING_DONATIONS, PENDING_WITHDRAWAL, REFUND_FEE_BPS, RETAINED_FEES,
+    TOTAL_WITHDRAWN, WITHDRAW_COOLDOWN,
+};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response,
-    StdError, StdResult, Uint128,
+    to_binary, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Reply,
+    Response, StdError, StdResult, Storage, SubMsg, SubMsgResult, Uint128,
 };
+use cw_storage_plus::{Bound, Map};
+
+/// default and maximum page size for paginated queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// maximum page size when filtering donations by amount
+const MAX_ABOVE_LIMIT: u32 = 50;
+
+/// maximum number of donations in the recent feed
+const MAX_RECENT_LIMIT: u32 = 50;
+
+/// number of outstanding donations a single withdrawal can safely process
+const WITHDRAW_BATCH_SIZE: u64 = 500;
+
+/// maximum refund fee in basis points
+const MAX_REFUND_FEE_BPS: u64 = 500;
+
+/// reply id for the admin withdrawal bank transfer, offset by the denom index
+const WITHDRAW_REPLY_ID: u64 = 1;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     // we set ourself as admin
     ADMIN.save(deps.storage, &info.sender)?;
+    INSTANTIATED_AT.save(deps.storage, &env.block.time.seconds())?;
+
+    // optional campaign window, start must not be after end
+    if let (Some(campaign_start), Some(campaign_end)) = (msg.campaign_start, msg.campaign_end) {
+        if campaign_start > campaign_end {
+            return Err(ContractError::InvalidCampaignWindow {});
+        }
+    }
+    if let Some(campaign_start) = msg.campaign_start {
+        CAMPAIGN_START.save(deps.storage, &campaign_start)?;
+    }
+    if let Some(campaign_end) = msg.campaign_end {
+        CAMPAIGN_END.save(deps.storage, &campaign_end)?;
+    }
+
+    // minimum donation, zero by default
+    MIN_DONATION.save(deps.storage, &msg.min_donation.unwrap_or_default())?;
+
+    // refunds are free unless a fee is configured
+    let refund_fee_bps = msg.refund_fee_bps.unwrap_or_default();
+    if refund_fee_bps > MAX_REFUND_FEE_BPS {
+        return Err(ContractError::RefundFeeTooHigh {
+            max: MAX_REFUND_FEE_BPS,
+        });
+    }
+    REFUND_FEE_BPS.save(deps.storage, &refund_fee_bps)?;
+
+    // admin withdrawals are not rate limited by default
+    WITHDRAW_COOLDOWN.save(deps.storage, &msg.withdraw_cooldown.unwrap_or_default())?;
+
+    // only uosmo is accepted by default
+    let accepted_denoms = msg.accepted_denoms.unwrap_or_else(|| vec![default_denom()]);
+    if accepted_denoms.is_empty() {
+        return Err(ContractError::NoAcceptedDenoms {});
+    }
+    ACCEPTED_DENOMS.save(deps.storage, &accepted_denoms)?;
+
+    // denoms without their own minimum fall back to the global one
+    for (denom, min) in msg.denom_min_donations.unwrap_or_default() {
+        DENOM_MIN_DONATIONS.save(deps.storage, &denom, &min)?;
+    }
+
+    // optional prefix for custom events
+    if let Some(event_prefix) = &msg.event_prefix {
+        validate_event_prefix(event_prefix)?;
+    }
+    EVENT_PREFIX.save(deps.storage, &msg.event_prefix.unwrap_or_default())?;
 
     Ok(Response::new().add_attribute("admin", info.sender))
 }
@@ -30,20 +110,60 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    match msg {
+    let event_prefix = EVENT_PREFIX.may_load(deps.storage)?.unwrap_or_default();
+
+    let res = match msg {
         ExecuteMsg::Deposit {} => try_deposit(deps, env, info),
-        ExecuteMsg::Withdraw {} => try_withdraw(deps, env, info),
-    }
+        ExecuteMsg::Withdraw { limit } => try_withdraw(deps, env, info, limit),
+        ExecuteMsg::SetMinDonation { amount } => try_set_min_donation(deps, info, amount),
+        ExecuteMsg::RefundAll {} => try_refund_all(deps, info),
+        ExecuteMsg::ReserveDonation { id } => try_set_reserved(deps, info, id, true),
+        ExecuteMsg::UnreserveDonation { id } => try_set_reserved(deps, info, id, false),
+        ExecuteMsg::PurgeZeroDonations { start_after, limit } => {
+            try_purge_zero_donations(deps, info, start_after, limit)
+        }
+        ExecuteMsg::WithdrawFees {} => try_withdraw_fees(deps, info),
+    }?;
+
+    Ok(add_prefixed_event(res, &event_prefix))
 }
 
-pub fn try_deposit(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
-    // validate uosmo sent
-    if info.funds.len() != 1 || info.funds[0].denom != "uosmo" {
+pub fn try_deposit(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    // reject donations before the campaign starts
+    if let Some(campaign_start) = CAMPAIGN_START.may_load(deps.storage)? {
+        if env.block.time.seconds() < campaign_start {
+            return Err(ContractError::CampaignNotStarted {});
+        }
+    }
+
+    // and after it ends
+    if let Some(campaign_end) = CAMPAIGN_END.may_load(deps.storage)? {
+        if env.block.time.seconds() > campaign_end {
+            return Err(ContractError::CampaignEnded {});
+        }
+    }
+
+    // validate a single accepted denom sent
+    let accepted_denoms = load_accepted_denoms(deps.as_ref())?;
+    if info.funds.len() != 1 || !accepted_denoms.contains(&info.funds[0].denom) {
         return Err(ContractError::Std(StdError::generic_err(
             "Invalid deposit!",
         )));
     }
 
+    // reject dust donations, the global minimum only ever priced the default denom
+    let denom = &info.funds[0].denom;
+    let min = match DENOM_MIN_DONATIONS.may_load(deps.storage, denom)? {
+        Some(min) => min,
+        None if *denom == default_denom() => {
+            MIN_DONATION.may_load(deps.storage)?.unwrap_or_default()
+        }
+        None => Uint128::zero(),
+    };
+    if info.funds[0].amount < min {
+        return Err(ContractError::DonationTooSmall { min });
+    }
+
     // retrieve current donation id
     let mut donation_id = DONATION_COUNT.load(deps.storage).unwrap_or_default();
 
@@ -53,10 +173,27 @@ pub fn try_deposit(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Respon
         donator: info.sender.clone(),
         amount: info.funds[0].amount,
         withdrawn: false,
+        created_at: env.block.time.seconds(),
+        reserved: false,
+        denom: info.funds[0].denom.clone(),
     };
 
+    // count first-time donators
+    let is_new_donator = DONATIONS_BY_USER
+        .prefix(&info.sender)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_none();
+    if is_new_donator {
+        let donator_count = DONATOR_COUNT.may_load(deps.storage)?.unwrap_or_default();
+        DONATOR_COUNT.save(deps.storage, &(donator_count + 1))?;
+    }
+
     // save donation info to storage
     DONATIONS.save(deps.storage, donation_id, &new_donation)?;
+    DONATIONS_BY_USER.save(deps.storage, (&info.sender, donation_id), &())?;
+    PENDING_DONATIONS.save(deps.storage, donation_id, &())?;
+    record_donated(deps.storage, &new_donation)?;
 
     // increment and save donation count
     donation_id += 1;
@@ -69,10 +206,83 @@ pub fn try_deposit(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Respon
         .add_attribute("next_donation_id", donation_id.to_string()))
 }
 
+/// accepted donation denoms, uosmo for contracts instantiated before they were configurable
+fn load_accepted_denoms(deps: Deps) -> StdResult<Vec<String>> {
+    Ok(ACCEPTED_DENOMS
+        .may_load(deps.storage)?
+        .unwrap_or_else(|| vec![default_denom()]))
+}
+
+/// add `amount` to the running total kept for `denom`
+fn add_denom_total(
+    storage: &mut dyn Storage,
+    totals: &Map<&str, Uint128>,
+    denom: &str,
+    amount: Uint128,
+) -> StdResult<()> {
+    let total = totals
+        .may_load(storage, denom)?
+        .unwrap_or_default()
+        .checked_add(amount)?;
+    totals.save(storage, denom, &total)
+}
+
+/// running per-denom totals as coins, ordered by denom
+fn load_denom_totals(storage: &dyn Storage, totals: &Map<&str, Uint128>) -> StdResult<Vec<Coin>> {
+    totals
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Coin { denom, amount })
+        })
+        .collect()
+}
+
+/// per-denom totals as coins, ordered by denom
+fn to_coins(totals: BTreeMap<String, Uint128>) -> Vec<Coin> {
+    totals
+        .into_iter()
+        .map(|(denom, amount)| Coin { denom, amount })
+        .collect()
+}
+
+/// comma separated coin list for attributes, e.g. `25uosmo,5uusd`
+fn format_coins(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(|coin| format!("{}{}", coin.amount, coin.denom))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// add a donation to the running per-denom totals
+fn record_donated(storage: &mut dyn Storage, donation: &Donation) -> StdResult<()> {
+    add_denom_total(storage, &DENOM_DONATED, &donation.denom, donation.amount)?;
+
+    let count = DENOM_DONATION_COUNT
+        .may_load(storage, &donation.denom)?
+        .unwrap_or_default();
+    DENOM_DONATION_COUNT.save(storage, &donation.denom, &(count + 1))?;
+
+    Ok(())
+}
+
+/// sum donation amounts per denom, ordered by denom
+fn totals_by_denom(donations: &[Donation]) -> StdResult<BTreeMap<String, Uint128>> {
+    let mut totals: BTreeMap<String, Uint128> = BTreeMap::new();
+    for donation in donations {
+        let total = totals.entry(donation.denom.clone()).or_default();
+        *total = total.checked_add(donation.amount)?;
+    }
+
+    Ok(totals)
+}
+
 pub fn try_withdraw(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
+    limit: Option<u32>,
 ) -> Result<Response, ContractError> {
     // load admin address from storage
     let admin_addr = ADMIN.load(deps.storage)?;
@@ -82,14 +292,36 @@ pub fn try_withdraw(
         return Err(ContractError::Unauthorized {});
     }
 
-    // donation amount to withdraw
-    let mut total_amount = Uint128::zero();
+    // rate limit admin sweeps
+    let now = env.block.time.seconds();
+    let last_withdraw = LAST_WITHDRAW.may_load(deps.storage)?;
+    if let Some(last_withdraw) = last_withdraw {
+        let cooldown = WITHDRAW_COOLDOWN
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        let retry_after = last_withdraw.saturating_add(cooldown);
+        if now < retry_after {
+            return Err(ContractError::CooldownActive { retry_after });
+        }
+    }
 
-    // find withdrawable donations
-    let withdrawable_donations = DONATIONS
-        .range(deps.storage, None, None, Order::Ascending)
-        .filter_map(|p| p.ok())
-        .filter(|t| !t.1.withdrawn)
+    // every pending donation unless the admin chunks the sweep
+    let limit = match limit {
+        Some(limit) => limit as usize,
+        None => usize::MAX,
+    };
+
+    // find withdrawable donations, only scanning the pending index
+    let withdrawable_donations = PENDING_DONATIONS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+        .map(|id| {
+            let id = id?;
+            Ok((id, DONATIONS.load(deps.storage, id)?))
+        })
+        .collect::<StdResult<Vec<(u64, Donation)>>>()?
+        .into_iter()
+        .filter(|t| !t.1.withdrawn && !t.1.reserved)
         .collect::<Vec<(u64, Donation)>>();
 
     // verify valid withdrawal
@@ -99,42 +331,447 @@ pub fn try_withdraw(
         }));
     }
 
-    for (id, mut donation) in withdrawable_donations {
-        // increase amount to withdraw
-        total_amount += donation.amount;
+    let mut pending_ids: Vec<u64> = vec![];
+    let mut donations: Vec<Donation> = vec![];
 
+    for (id, mut donation) in withdrawable_donations {
         // set withdrawn as true to prevent double withdrawal
         donation.withdrawn = true;
 
         // save to storage
         DONATIONS.save(deps.storage, id, &donation)?;
+        PENDING_DONATIONS.remove(deps.storage, id);
+        pending_ids.push(id);
+        donations.push(donation);
     }
 
-    // send rewards to admin
-    let msg = CosmosMsg::Bank(BankMsg::Send {
-        to_address: info.sender.to_string(),
-        amount: vec![Coin {
-            denom: "uosmo".to_string(),
-            amount: total_amount,
-        }],
-    });
+    // remember marked donations in case the transfer fails
+    PENDING_WITHDRAWAL.save(
+        deps.storage,
+        &PendingWithdrawal {
+            ids: pending_ids,
+            last_withdraw,
+        },
+    )?;
+    LAST_WITHDRAW.save(deps.storage, &now)?;
+
+    // send rewards to admin, one transfer per denom so a failure only rolls back its own denom
+    let totals = to_coins(totals_by_denom(&donations)?);
+    let mut msgs: Vec<SubMsg> = vec![];
+    for (index, total) in totals.iter().enumerate() {
+        add_denom_total(deps.storage, &TOTAL_WITHDRAWN, &total.denom, total.amount)?;
+
+        let msg = CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![total.clone()],
+        });
+        msgs.push(SubMsg::reply_always(msg, WITHDRAW_REPLY_ID + index as u64));
+    }
 
     Ok(Response::new()
-        .add_message(msg)
+        .add_submessages(msgs)
         .add_attribute("method", "withdraw")
-        .add_attribute("total_amount", total_amount)
+        .add_attribute("total_amount", format_coins(&totals))
         .add_attribute("sender", info.sender))
 }
 
+pub fn try_refund_all(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    // find the caller's pending donations through the per-user index
+    let pending = DONATIONS_BY_USER
+        .prefix(&info.sender)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|id| DONATIONS.load(deps.storage, id?))
+        .collect::<StdResult<Vec<Donation>>>()?
+        .into_iter()
+        .filter(|d| !d.withdrawn)
+        .collect::<Vec<Donation>>();
+
+    // verify valid refund
+    if pending.is_empty() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Nothing to refund!",
+        )));
+    }
+
+    for donation in &pending {
+        DONATIONS.remove(deps.storage, donation.id);
+        DONATIONS_BY_USER.remove(deps.storage, (&info.sender, donation.id));
+        PENDING_DONATIONS.remove(deps.storage, donation.id);
+    }
+
+    // donator no longer has any donation on record
+    let has_donations = DONATIONS_BY_USER
+        .prefix(&info.sender)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    if !has_donations {
+        let donator_count = DONATOR_COUNT.may_load(deps.storage)?.unwrap_or_default();
+        DONATOR_COUNT.save(deps.storage, &donator_count.saturating_sub(1))?;
+    }
+
+    // contract keeps the refund fee
+    let refund_fee_bps = REFUND_FEE_BPS.may_load(deps.storage)?.unwrap_or_default();
+    let totals = to_coins(totals_by_denom(&pending)?);
+    let mut fees: Vec<Coin> = vec![];
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    for Coin { denom, amount } in totals.iter().cloned() {
+        let fee = amount.multiply_ratio(refund_fee_bps, 10_000_u64);
+        add_denom_total(deps.storage, &RETAINED_FEES, &denom, fee)?;
+        fees.push(Coin::new(fee.u128(), denom.clone()));
+
+        // return funds to donator
+        msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                denom,
+                amount: amount - fee,
+            }],
+        }));
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("method", "refund_all")
+        .add_attribute("total_amount", format_coins(&totals))
+        .add_attribute("refunded", pending.len().to_string())
+        .add_attribute("sender", info.sender)
+        .add_attribute("fee", format_coins(&fees)))
+}
+
+/// pay the refund fees retained so far out to the admin
+pub fn try_withdraw_fees(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    // load admin address from storage
+    let admin_addr = ADMIN.load(deps.storage)?;
+
+    // verify sender is admin
+    if info.sender != admin_addr {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let fees = load_denom_totals(deps.storage, &RETAINED_FEES)?
+        .into_iter()
+        .filter(|fee| !fee.amount.is_zero())
+        .collect::<Vec<Coin>>();
+
+    // verify valid withdrawal
+    if fees.is_empty() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Nothing to withdraw!",
+        )));
+    }
+
+    // fees leave the contract in full
+    let denoms = RETAINED_FEES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<String>>>()?;
+    for denom in denoms {
+        RETAINED_FEES.remove(deps.storage, &denom);
+    }
+
+    let amount = format_coins(&fees);
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: fees,
+        }))
+        .add_attribute("method", "withdraw_fees")
+        .add_attribute("amount", amount))
+}
+
+/// donators hold back a pending donation from admin withdrawals, keeping it refundable
+pub fn try_set_reserved(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    reserved: bool,
+) -> Result<Response, ContractError> {
+    let mut donation = DONATIONS.load(deps.storage, id)?;
+
+    // only the donator can reserve their donation
+    if donation.donator != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // nothing left to reserve once swept
+    if donation.withdrawn {
+        return Err(ContractError::DonationWithdrawn {});
+    }
+
+    donation.reserved = reserved;
+    DONATIONS.save(deps.storage, id, &donation)?;
+
+    // reserved donations drop out of the sweep index so limited withdrawals skip them
+    if reserved {
+        PENDING_DONATIONS.remove(deps.storage, id);
+    } else {
+        PENDING_DONATIONS.save(deps.storage, id, &())?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "set_reserved")
+        .add_attribute("id", id.to_string())
+        .add_attribute("reserved", reserved.to_string()))
+}
+
+pub fn try_purge_zero_donations(
+    deps: DepsMut,
+    info: MessageInfo,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    // load admin address from storage
+    let admin_addr = ADMIN.load(deps.storage)?;
+
+    // verify sender is admin
+    if info.sender != admin_addr {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    // scan a bounded window, zero amount donations only exist in legacy data
+    let scanned = DONATIONS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, donation)| donation))
+        .collect::<StdResult<Vec<Donation>>>()?;
+    let zero_donations = scanned
+        .iter()
+        .filter(|d| d.amount.is_zero())
+        .collect::<Vec<&Donation>>();
+
+    for donation in &zero_donations {
+        // legacy donations were never indexed and never counted their donator
+        let indexed = DONATIONS_BY_USER.has(deps.storage, (&donation.donator, donation.id));
+
+        DONATIONS.remove(deps.storage, donation.id);
+        DONATIONS_BY_USER.remove(deps.storage, (&donation.donator, donation.id));
+        PENDING_DONATIONS.remove(deps.storage, donation.id);
+
+        // donator no longer has any donation on record
+        let has_donations = DONATIONS_BY_USER
+            .prefix(&donation.donator)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .next()
+            .is_some();
+        if indexed && !has_donations {
+            let donator_count = DONATOR_COUNT.may_load(deps.storage)?.unwrap_or_default();
+            DONATOR_COUNT.save(deps.storage, &donator_count.saturating_sub(1))?;
+        }
+    }
+
+    let mut res = Response::new()
+        .add_attribute("method", "purge_zero_donations")
+        .add_attribute("purged", zero_donations.len().to_string());
+
+    // cursor for the next window
+    if let Some(last) = scanned.last() {
+        res = res.add_attribute("last_scanned", last.id.to_string());
+    }
+
+    Ok(res)
+}
+
+pub fn try_set_min_donation(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    // load admin address from storage
+    let admin_addr = ADMIN.load(deps.storage)?;
+
+    // verify sender is admin
+    if info.sender != admin_addr {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    MIN_DONATION.save(deps.storage, &amount)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_min_donation")
+        .add_attribute("amount", amount))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let pending_withdrawal = match PENDING_WITHDRAWAL.may_load(deps.storage)? {
+        Some(pending_withdrawal) => pending_withdrawal,
+        None => {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Unknown reply id",
+            )))
+        }
+    };
+    let pending = pending_withdrawal
+        .ids
+        .iter()
+        .map(|id| DONATIONS.load(deps.storage, *id))
+        .collect::<StdResult<Vec<Donation>>>()?;
+
+    // reply ids follow the denom order of the withdrawal transfers
+    let denoms = totals_by_denom(&pending)?;
+    let denom_count = denoms.len() as u64;
+    let (index, denom) = match msg.id.checked_sub(WITHDRAW_REPLY_ID).and_then(|index| {
+        let denom = denoms.into_keys().nth(index as usize)?;
+        Some((index, denom))
+    }) {
+        Some(reply) => reply,
+        None => {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Unknown reply id",
+            )))
+        }
+    };
+
+    // the last transfer settles the withdrawal
+    if index + 1 == denom_count {
+        PENDING_WITHDRAWAL.remove(deps.storage);
+    }
+
+    let error = match msg.result {
+        SubMsgResult::Err(error) => error,
+        SubMsgResult::Ok(_) => return Ok(Response::new()),
+    };
+
+    // a failed sweep does not start the cooldown
+    match pending_withdrawal.last_withdraw {
+        Some(last_withdraw) => LAST_WITHDRAW.save(deps.storage, &last_withdraw)?,
+        None => LAST_WITHDRAW.remove(deps.storage),
+    }
+
+    // transfer failed, make the donations in its denom withdrawable again
+    let mut restored = 0_u64;
+    let mut restored_amount = Uint128::zero();
+    for mut donation in pending
+        .into_iter()
+        .filter(|d| d.denom == denom && d.withdrawn)
+    {
+        donation.withdrawn = false;
+        DONATIONS.save(deps.storage, donation.id, &donation)?;
+        PENDING_DONATIONS.save(deps.storage, donation.id, &())?;
+        restored += 1;
+        restored_amount += donation.amount;
+    }
+
+    // the sweep never left the contract
+    let total_withdrawn = TOTAL_WITHDRAWN
+        .may_load(deps.storage, &denom)?
+        .unwrap_or_default()
+        .saturating_sub(restored_amount);
+    if total_withdrawn.is_zero() {
+        TOTAL_WITHDRAWN.remove(deps.storage, &denom);
+    } else {
+        TOTAL_WITHDRAWN.save(deps.storage, &denom, &total_withdrawn)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "withdraw_rollback")
+        .add_attribute("restored", restored.to_string())
+        .add_attribute("error", error))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // re-save donations so legacy entries store `created_at: 0` and the uosmo denom
+    let donations = DONATIONS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(u64, Donation)>>>()?;
+
+    for (id, donation) in &donations {
+        DONATIONS.save(deps.storage, *id, donation)?;
+
+        // backfill the pending index
+        if !donation.withdrawn && !donation.reserved {
+            PENDING_DONATIONS.save(deps.storage, *id, &())?;
+        } else {
+            PENDING_DONATIONS.remove(deps.storage, *id);
+        }
+    }
+
+    // backfill running totals for contracts that predate them
+    let has_totals = DENOM_DONATION_COUNT
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    if !has_totals {
+        for (_, donation) in &donations {
+            record_donated(deps.storage, donation)?;
+            if donation.withdrawn {
+                add_denom_total(
+                    deps.storage,
+                    &TOTAL_WITHDRAWN,
+                    &donation.denom,
+                    donation.amount,
+                )?;
+            }
+        }
+    }
+
+    // legacy contracts only ever accepted uosmo
+    if ACCEPTED_DENOMS.may_load(deps.storage)?.is_none() {
+        ACCEPTED_DENOMS.save(deps.storage, &vec![default_denom()])?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("donations", donations.len().to_string()))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetNextDonationId {} => to_binary(&query_next_id(deps)?),
         QueryMsg::GetAllDonations {} => to_binary(&query_all_donations(deps)?),
         QueryMsg::GetDonationInfo { id } => to_binary(&query_donation(deps, id)?),
+        QueryMsg::GetDonationsByDonator {
+            donator,
+            start_after,
+            limit,
+        } => to_binary(&query_donations_by_donator(
+            deps,
+            donator,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::GetDonationsAbove {
+            denom,
+            threshold,
+            limit,
+        } => to_binary(&query_donations_above(deps, denom, threshold, limit)?),
+        QueryMsg::CanWithdrawAll {} => to_binary(&query_can_withdraw_all(deps)?),
+        QueryMsg::GetAdminWithdrawable {} => to_binary(&query_admin_withdrawable(deps)?),
+        QueryMsg::GetConfig {} => to_binary(&query_config(deps)?),
+        QueryMsg::GetStats {} => to_binary(&query_stats(deps)?),
+        QueryMsg::GetAverageDonation { denom } => to_binary(&query_average_donation(deps, denom)?),
+        QueryMsg::GetRecentDonations { count } => to_binary(&query_recent_donations(deps, count)?),
+        QueryMsg::GetInstantiatedAt {} => to_binary(&query_instantiated_at(deps)?),
+        QueryMsg::GetTopDonation { denom } => to_binary(&query_top_donation(deps, denom)?),
+        QueryMsg::GetTotalWithdrawn {} => to_binary(&query_total_withdrawn(deps)?),
+        #[cfg(feature = "debug")]
+        QueryMsg::DebugDump { start_after, limit } => {
+            to_binary(&query_debug_dump(deps, start_after, limit)?)
+        }
     }
 }
 
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    Ok(ConfigResponse {
+        admin: ADMIN.load(deps.storage)?,
+        campaign_start: CAMPAIGN_START.may_load(deps.storage)?,
+        campaign_end: CAMPAIGN_END.may_load(deps.storage)?,
+        min_donation: MIN_DONATION.may_load(deps.storage)?.unwrap_or_default(),
+        refund_fee_bps: REFUND_FEE_BPS.may_load(deps.storage)?.unwrap_or_default(),
+        accepted_denoms: load_accepted_denoms(deps)?,
+    })
+}
+
+fn query_instantiated_at(deps: Deps) -> StdResult<u64> {
+    Ok(INSTANTIATED_AT.may_load(deps.storage)?.unwrap_or_default())
+}
+
 fn query_next_id(deps: Deps) -> StdResult<NextDonationId> {
     let next_id = DONATION_COUNT.load(deps.storage).unwrap_or_default();
     Ok(NextDonationId { next_id })
@@ -156,17 +793,190 @@ fn query_donation(deps: Deps, id: u64) -> StdResult<Donation> {
     Ok(donation_info)
 }
 
+/// paginate donations of a single donator using the per-user index
+fn query_donations_by_donator(
+    deps: Deps,
+    donator: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<AllDonations> {
+    let donator = deps.api.addr_validate(&donator)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let donations = DONATIONS_BY_USER
+        .prefix(&donator)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|id| DONATIONS.load(deps.storage, id?))
+        .collect::<StdResult<Vec<Donation>>>()?;
+
+    Ok(AllDonations { donations })
+}
+
+/// paginate donations alongside their hex-encoded raw storage keys
+#[cfg(feature = "debug")]
+fn query_debug_dump(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<DebugDumpResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let entries = DONATIONS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|v| {
+            let (id, donation) = v?;
+            let key = DONATIONS
+                .key(id)
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            Ok(DebugEntry { key, donation })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(DebugDumpResponse { entries })
+}
+
+/// collect donations with amount greater than or equal to the threshold
+fn query_donations_above(
+    deps: Deps,
+    denom: String,
+    threshold: Uint128,
+    limit: Option<u32>,
+) -> StdResult<AllDonations> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_ABOVE_LIMIT) as usize;
+
+    // amounts are only comparable within a denom
+    let donations = DONATIONS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|v| {
+            v.as_ref()
+                .map_or(true, |(_, d)| d.denom == denom && d.amount >= threshold)
+        })
+        .take(limit)
+        .map(|v| Ok(v?.1))
+        .collect::<StdResult<Vec<Donation>>>()?;
+
+    Ok(AllDonations { donations })
+}
+
+/// latest donations, newest first
+fn query_recent_donations(deps: Deps, count: u32) -> StdResult<AllDonations> {
+    let count = count.min(MAX_RECENT_LIMIT) as usize;
+
+    let donations = DONATIONS
+        .range(deps.storage, None, None, Order::Descending)
+        .take(count)
+        .map(|v| Ok(v?.1))
+        .collect::<StdResult<Vec<Donation>>>()?;
+
+    Ok(AllDonations { donations })
+}
+
+/// largest donation in `denom`, the lowest id wins ties
+fn query_top_donation(deps: Deps, denom: String) -> StdResult<Option<Donation>> {
+    let mut top: Option<Donation> = None;
+    for item in DONATIONS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, donation) = item?;
+        if donation.denom != denom {
+            continue;
+        }
+        let larger = match &top {
+            Some(t) => donation.amount > t.amount,
+            None => true,
+        };
+        if larger {
+            top = Some(donation);
+        }
+    }
+
+    Ok(top)
+}
+
+fn query_total_withdrawn(deps: Deps) -> StdResult<Vec<Coin>> {
+    load_denom_totals(deps.storage, &TOTAL_WITHDRAWN)
+}
+
+/// check whether outstanding donations fit in a single `Withdraw {}`, otherwise
+/// `recommended_batches` calls of `Withdraw { limit }` with the batch size clear them
+fn query_can_withdraw_all(deps: Deps) -> StdResult<CanWithdrawAllResponse> {
+    // reserved donations are not in the pending index and never count against a batch
+    let outstanding_count = PENDING_DONATIONS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .count() as u64;
+
+    Ok(CanWithdrawAllResponse {
+        safe: outstanding_count <= WITHDRAW_BATCH_SIZE,
+        outstanding_count,
+        recommended_batches: outstanding_count.div_ceil(WITHDRAW_BATCH_SIZE) as u32,
+    })
+}
+
+/// sum donations the admin can sweep, excluding those reserved for refunds
+fn query_admin_withdrawable(deps: Deps) -> StdResult<AdminWithdrawableResponse> {
+    let mut withdrawable: Vec<Donation> = vec![];
+    for donation in DONATIONS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, donation) = donation?;
+        if !donation.withdrawn && !donation.reserved {
+            withdrawable.push(donation);
+        }
+    }
+
+    Ok(AdminWithdrawableResponse {
+        amounts: to_coins(totals_by_denom(&withdrawable)?),
+    })
+}
+
+/// aggregate donation totals from the running counters, without scanning donations
+fn query_stats(deps: Deps) -> StdResult<StatsResponse> {
+    Ok(StatsResponse {
+        total_donations: DONATION_COUNT.may_load(deps.storage)?.unwrap_or_default(),
+        total_amount: load_denom_totals(deps.storage, &DENOM_DONATED)?,
+        withdrawn_amount: load_denom_totals(deps.storage, &TOTAL_WITHDRAWN)?,
+        donator_count: DONATOR_COUNT.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+/// mean amount over every donation made in `denom`, truncated, zero without donations
+fn query_average_donation(deps: Deps, denom: String) -> StdResult<Uint128> {
+    let count = DENOM_DONATION_COUNT
+        .may_load(deps.storage, &denom)?
+        .unwrap_or_default();
+    if count == 0 {
+        return Ok(Uint128::zero());
+    }
+
+    let donated = DENOM_DONATED
+        .may_load(deps.storage, &denom)?
+        .unwrap_or_default();
+    Ok(donated / Uint128::from(count))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary};
+    use cosmwasm_std::{coins, from_binary, Addr, Empty, Event, Storage, Timestamp};
+    use cw_multi_test::{App, Contract, ContractWrapper, Executor};
 
     #[test]
     fn deposit_withdraw_success() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
         let info = mock_info("admin", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -191,7 +1001,8 @@ mod tests {
         assert_eq!(value.id, 0);
         assert_eq!(value.donator, "alice");
         assert_eq!(value.amount, Uint128::from(10_u64));
-        assert_eq!(value.withdrawn, false);
+        assert!(!value.withdrawn);
+        assert_eq!(value.created_at, mock_env().block.time.seconds());
 
         // make sure donation id incremented
         let res = query(deps.as_ref(), mock_env(), QueryMsg::GetNextDonationId {}).unwrap();
@@ -214,7 +1025,7 @@ mod tests {
         assert_eq!(value.id, 1);
         assert_eq!(value.donator, "alice");
         assert_eq!(value.amount, Uint128::from(20_u64));
-        assert_eq!(value.withdrawn, false);
+        assert!(!value.withdrawn);
 
         // test query all donations
         let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAllDonations {}).unwrap();
@@ -223,12 +1034,12 @@ mod tests {
 
         // withdraw donations
         let info = mock_info("admin", &[]);
-        let msg = ExecuteMsg::Withdraw {};
+        let msg = ExecuteMsg::Withdraw { limit: None };
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
         // verify withdrawal succeed
         assert_eq!(res.attributes[0].value, "withdraw");
-        assert_eq!(res.attributes[1].value, "30");
+        assert_eq!(res.attributes[1].value, "30uosmo");
         assert_eq!(res.attributes[2].value, "admin");
     }
 
@@ -237,7 +1048,16 @@ mod tests {
     fn deposit_failure() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
         let info = mock_info("admin", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -252,21 +1072,1672 @@ mod tests {
     fn withdraw_fail() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
         let info = mock_info("admin", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
         // only admin can withdraw
         let info = mock_info("bob", &[]);
-        let msg = ExecuteMsg::Withdraw {};
+        let msg = ExecuteMsg::Withdraw { limit: None };
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     }
 
+    #[test]
+    fn deposit_min_donation() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: Some(Uint128::from(10_u64)),
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let value: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(value.admin, "admin");
+        assert_eq!(value.campaign_start, None);
+        assert_eq!(value.min_donation, Uint128::from(10_u64));
+
+        // just below the threshold
+        let info = mock_info("alice", &coins(9, "uosmo"));
+        let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap_err();
+        match err {
+            ContractError::DonationTooSmall { min } => assert_eq!(min, Uint128::from(10_u64)),
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // at the threshold
+        let info = mock_info("alice", &coins(10, "uosmo"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetNextDonationId {}).unwrap();
+        let value: NextDonationId = from_binary(&res).unwrap();
+        assert_eq!(value.next_id, 1);
+    }
+
+    #[test]
+    fn deposit_denom_min_donation() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: Some(Uint128::from(50_u64)),
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: Some(vec![
+                "uosmo".to_string(),
+                "uusd".to_string(),
+                "ujuno".to_string(),
+            ]),
+            denom_min_donations: Some(vec![
+                ("uosmo".to_string(), Uint128::from(100_u64)),
+                ("uusd".to_string(), Uint128::from(5_u64)),
+            ]),
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // below each denom's own minimum
+        for (amount, denom, expected) in [(99_u128, "uosmo", 100_u64), (4, "uusd", 5)] {
+            let info = mock_info("alice", &coins(amount, denom));
+            let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap_err();
+            match err {
+                ContractError::DonationTooSmall { min } => {
+                    assert_eq!(min, Uint128::from(expected))
+                }
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        // at the minimum, unlisted denoms have none even below the global minimum
+        for (amount, denom) in [(100_u128, "uosmo"), (5, "uusd"), (1, "ujuno")] {
+            let info = mock_info("alice", &coins(amount, denom));
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetNextDonationId {}).unwrap();
+        let value: NextDonationId = from_binary(&res).unwrap();
+        assert_eq!(value.next_id, 3);
+    }
+
+    #[test]
+    fn set_min_donation() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // only the admin can tune the minimum
+        let msg = ExecuteMsg::SetMinDonation {
+            amount: Uint128::from(50_u64),
+        };
+        let info = mock_info("alice", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let info = mock_info("admin", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let value: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(value.min_donation, Uint128::from(50_u64));
+
+        // deposits below the new minimum are rejected
+        let info = mock_info("alice", &coins(49, "uosmo"));
+        let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap_err();
+        match err {
+            ContractError::DonationTooSmall { min } => assert_eq!(min, Uint128::from(50_u64)),
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let info = mock_info("alice", &coins(50, "uosmo"));
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+    }
+
+    #[test]
+    fn deposit_campaign_start() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let start = mock_env().block.time.plus_seconds(60).seconds();
+        let msg = InstantiateMsg {
+            campaign_start: Some(start),
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // cannot donate before the campaign starts
+        let info = mock_info("alice", &coins(10, "uosmo"));
+        let msg = ExecuteMsg::Deposit {};
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::CampaignNotStarted {}));
+
+        // able to donate once the campaign started
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(start);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetNextDonationId {}).unwrap();
+        let value: NextDonationId = from_binary(&res).unwrap();
+        assert_eq!(value.next_id, 1_u64);
+    }
+
+    #[test]
+    fn campaign_window() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        // start after end is rejected
+        let start = mock_env().block.time.seconds();
+        let msg = InstantiateMsg {
+            campaign_start: Some(start + 60),
+            campaign_end: Some(start),
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidCampaignWindow {}));
+
+        let msg = InstantiateMsg {
+            campaign_start: Some(start),
+            campaign_end: Some(start + 60),
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let value: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(value.campaign_start, Some(start));
+        assert_eq!(value.campaign_end, Some(start + 60));
+
+        // donations are accepted up to and including the end
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(start + 60);
+        let info = mock_info("alice", &coins(10, "uosmo"));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Deposit {},
+        )
+        .unwrap();
+
+        env.block.time = Timestamp::from_seconds(start + 61);
+        let err = execute(deps.as_mut(), env, info, ExecuteMsg::Deposit {}).unwrap_err();
+        assert!(matches!(err, ContractError::CampaignEnded {}));
+    }
+
+    #[test]
+    fn donations_by_donator() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // alice and bob donate interleaved
+        for donator in ["alice", "bob", "alice", "bob", "alice"] {
+            let info = mock_info(donator, &coins(10, "uosmo"));
+            let msg = ExecuteMsg::Deposit {};
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetDonationsByDonator {
+                donator: "alice".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: AllDonations = from_binary(&res).unwrap();
+        let ids: Vec<u64> = value.donations.iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![0, 2, 4]);
+        assert!(value.donations.iter().all(|d| d.donator == "alice"));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetDonationsByDonator {
+                donator: "bob".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: AllDonations = from_binary(&res).unwrap();
+        let ids: Vec<u64> = value.donations.iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![1, 3]);
+
+        // paginate alice's donations
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetDonationsByDonator {
+                donator: "alice".to_string(),
+                start_after: Some(0),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let value: AllDonations = from_binary(&res).unwrap();
+        let ids: Vec<u64> = value.donations.iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![2]);
+    }
+
+    #[test]
+    fn donations_above() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: Some(vec!["uosmo".to_string(), "uusd".to_string()]),
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // donate mixed amounts
+        for amount in [5_u128, 100, 50, 99, 200] {
+            let info = mock_info("alice", &coins(amount, "uosmo"));
+            let msg = ExecuteMsg::Deposit {};
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        // a larger amount in another denom does not qualify
+        let info = mock_info("bob", &coins(500, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetDonationsAbove {
+                denom: "uosmo".to_string(),
+                threshold: Uint128::from(99_u64),
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: AllDonations = from_binary(&res).unwrap();
+        let ids: Vec<u64> = value.donations.iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![1, 3, 4]);
+
+        // limit applies to qualifying donations
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetDonationsAbove {
+                denom: "uosmo".to_string(),
+                threshold: Uint128::from(99_u64),
+                limit: Some(2),
+            },
+        )
+        .unwrap();
+        let value: AllDonations = from_binary(&res).unwrap();
+        let ids: Vec<u64> = value.donations.iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![1, 3]);
+    }
+
+    #[test]
+    fn can_withdraw_all() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // few donations are safe to withdraw at once
+        let info = mock_info("alice", &coins(10, "uosmo"));
+        let msg = ExecuteMsg::Deposit {};
+        for _ in 0..3 {
+            execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::CanWithdrawAll {}).unwrap();
+        let value: CanWithdrawAllResponse = from_binary(&res).unwrap();
+        assert!(value.safe);
+        assert_eq!(value.outstanding_count, 3);
+        assert_eq!(value.recommended_batches, 1);
+
+        // too many donations need chunking
+        for _ in 0..WITHDRAW_BATCH_SIZE {
+            execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::CanWithdrawAll {}).unwrap();
+        let value: CanWithdrawAllResponse = from_binary(&res).unwrap();
+        assert!(!value.safe);
+        assert_eq!(value.outstanding_count, WITHDRAW_BATCH_SIZE + 3);
+        assert_eq!(value.recommended_batches, 2);
+
+        // one limited withdrawal clears a batch
+        let msg = ExecuteMsg::Withdraw {
+            limit: Some(WITHDRAW_BATCH_SIZE as u32),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+        assert_eq!(
+            res.attributes[1].value,
+            format!("{}uosmo", WITHDRAW_BATCH_SIZE * 10)
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::CanWithdrawAll {}).unwrap();
+        let value: CanWithdrawAllResponse = from_binary(&res).unwrap();
+        assert!(value.safe);
+        assert_eq!(value.outstanding_count, 3);
+        assert_eq!(value.recommended_batches, 1);
+    }
+
+    #[test]
+    fn withdraw_cooldown() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: Some(3_600),
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("alice", &coins(10, "uosmo"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        let info = mock_info("admin", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Withdraw { limit: None },
+        )
+        .unwrap();
+
+        // a second sweep right away is rejected
+        let info = mock_info("alice", &coins(20, "uosmo"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        let info = mock_info("admin", &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Withdraw { limit: None },
+        )
+        .unwrap_err();
+        let retry_after = mock_env().block.time.seconds() + 3_600;
+        match err {
+            ContractError::CooldownActive { retry_after: t } => assert_eq!(t, retry_after),
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // allowed once the cooldown has passed
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(retry_after);
+        let info = mock_info("admin", &[]);
+        let res = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::Withdraw { limit: None },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[1].value, "20uosmo");
+    }
+
+    #[test]
+    fn total_withdrawn() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let total_withdrawn = |deps: Deps| -> Vec<Coin> {
+            let res = query(deps, mock_env(), QueryMsg::GetTotalWithdrawn {}).unwrap();
+            from_binary(&res).unwrap()
+        };
+        assert_eq!(total_withdrawn(deps.as_ref()), vec![]);
+
+        // successive sweeps accumulate
+        for (amount, expected) in [(10_u128, 10_u128), (25, 35)] {
+            let info = mock_info("alice", &coins(amount, "uosmo"));
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+            let info = mock_info("admin", &[]);
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                ExecuteMsg::Withdraw { limit: None },
+            )
+            .unwrap();
+            assert_eq!(total_withdrawn(deps.as_ref()), coins(expected, "uosmo"));
+        }
+    }
+
+    #[test]
+    fn admin_withdrawable() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for amount in [10_u128, 20, 30] {
+            let info = mock_info("alice", &coins(amount, "uosmo"));
+            let msg = ExecuteMsg::Deposit {};
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        // only the donator can reserve a donation
+        let msg = ExecuteMsg::ReserveDonation { id: 1 };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // alice reserves the second and third donations for a refund, then releases the third
+        for id in [1_u64, 2] {
+            let msg = ExecuteMsg::ReserveDonation { id };
+            execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        }
+        let msg = ExecuteMsg::UnreserveDonation { id: 2 };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        assert!(DONATIONS.load(deps.as_ref().storage, 1).unwrap().reserved);
+        assert!(!DONATIONS.load(deps.as_ref().storage, 2).unwrap().reserved);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAdminWithdrawable {}).unwrap();
+        let value: AdminWithdrawableResponse = from_binary(&res).unwrap();
+        assert_eq!(value.amounts, coins(40, "uosmo"));
+
+        // admin withdrawal matches the reported figure
+        let info = mock_info("admin", &[]);
+        let msg = ExecuteMsg::Withdraw { limit: None };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "40uosmo");
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAdminWithdrawable {}).unwrap();
+        let value: AdminWithdrawableResponse = from_binary(&res).unwrap();
+        assert_eq!(value.amounts, vec![]);
+
+        // swept donations cannot be reserved
+        let msg = ExecuteMsg::ReserveDonation { id: 0 };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::DonationWithdrawn {}));
+    }
+
+    #[test]
+    fn limited_withdraw_skips_reserved() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for amount in [10_u128, 20, 30] {
+            let info = mock_info("alice", &coins(amount, "uosmo"));
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+
+        // the oldest donations are reserved for a refund
+        for id in [0_u64, 1] {
+            let msg = ExecuteMsg::ReserveDonation { id };
+            execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        }
+
+        // reserved donations are not outstanding for the admin
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::CanWithdrawAll {}).unwrap();
+        let value: CanWithdrawAllResponse = from_binary(&res).unwrap();
+        assert_eq!(value.outstanding_count, 1);
+
+        // a batch of one reaches past them
+        let msg = ExecuteMsg::Withdraw { limit: Some(1) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+        assert_eq!(res.attributes[1].value, "30uosmo");
+        assert!(DONATIONS.load(deps.as_ref().storage, 2).unwrap().withdrawn);
+
+        // releasing a reservation makes it sweepable again
+        let msg = ExecuteMsg::UnreserveDonation { id: 0 };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        assert!(PENDING_DONATIONS.has(deps.as_ref().storage, 0));
+        assert!(!PENDING_DONATIONS.has(deps.as_ref().storage, 1));
+    }
+
+    #[test]
+    fn migrate_backfills_created_at() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        // donation stored before `created_at` existed
+        deps.storage.set(
+            &DONATIONS.key(0_u64),
+            br#"{"id":0,"donator":"alice","amount":"10","withdrawn":false}"#,
+        );
+
+        let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetDonationInfo { id: 0 },
+        )
+        .unwrap();
+        let value: Donation = from_binary(&res).unwrap();
+        assert_eq!(value.donator, "alice");
+        assert_eq!(value.created_at, 0);
+        assert_eq!(value.denom, "uosmo");
+        assert_eq!(
+            ACCEPTED_DENOMS.load(deps.as_ref().storage).unwrap(),
+            vec!["uosmo".to_string()]
+        );
+
+        // running totals are rebuilt once, a second migrate does not double them
+        let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(
+            DENOM_DONATED.load(deps.as_ref().storage, "uosmo").unwrap(),
+            Uint128::new(10)
+        );
+        assert_eq!(
+            DENOM_DONATION_COUNT
+                .load(deps.as_ref().storage, "uosmo")
+                .unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn multi_denom_withdraw() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        // at least one denom is required
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: Some(vec![]),
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoAcceptedDenoms {}));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: Some(vec!["uusd".to_string(), "uosmo".to_string()]),
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for (amount, denom) in [(10_u128, "uusd"), (20, "uosmo"), (5, "uusd")] {
+            let info = mock_info("alice", &coins(amount, denom));
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+        assert_eq!(
+            DONATIONS.load(deps.as_ref().storage, 0).unwrap().denom,
+            "uusd"
+        );
+
+        // denoms outside the accepted list are rejected
+        let info = mock_info("alice", &coins(10, "ujuno"));
+        let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap_err();
+        assert_eq!(err.to_string(), "Generic error: Invalid deposit!");
+
+        // one transfer per denom
+        let info = mock_info("admin", &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Withdraw { limit: None },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::reply_always(
+                    BankMsg::Send {
+                        to_address: "admin".to_string(),
+                        amount: coins(20, "uosmo"),
+                    },
+                    WITHDRAW_REPLY_ID,
+                ),
+                SubMsg::reply_always(
+                    BankMsg::Send {
+                        to_address: "admin".to_string(),
+                        amount: coins(15, "uusd"),
+                    },
+                    WITHDRAW_REPLY_ID + 1,
+                ),
+            ]
+        );
+
+        // totals are reported per denom rather than summed
+        assert_eq!(res.attributes[1].value, "20uosmo,15uusd");
+    }
+
+    #[test]
+    fn purge_zero_donations() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // legacy zero donations mixed with real ones, none of them indexed
+        for (id, amount) in [0_u128, 10, 0, 0, 20].iter().enumerate() {
+            let donation = Donation {
+                id: id as u64,
+                donator: Addr::unchecked("alice"),
+                amount: Uint128::from(*amount),
+                withdrawn: false,
+                created_at: 0,
+                reserved: false,
+                denom: "uosmo".to_string(),
+            };
+            DONATIONS
+                .save(deps.as_mut().storage, id as u64, &donation)
+                .unwrap();
+        }
+        DONATION_COUNT.save(deps.as_mut().storage, &5).unwrap();
+
+        // bob donates normally and carol holds an indexed zero donation
+        let info = mock_info("bob", &coins(10, "uosmo"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        let carol = Addr::unchecked("carol");
+        let donation = Donation {
+            id: 6,
+            donator: carol.clone(),
+            amount: Uint128::zero(),
+            withdrawn: false,
+            created_at: 0,
+            reserved: false,
+            denom: "uosmo".to_string(),
+        };
+        DONATIONS.save(deps.as_mut().storage, 6, &donation).unwrap();
+        DONATIONS_BY_USER
+            .save(deps.as_mut().storage, (&carol, 6), &())
+            .unwrap();
+        DONATOR_COUNT.save(deps.as_mut().storage, &2).unwrap();
+
+        // only admin can purge
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::PurgeZeroDonations {
+            start_after: None,
+            limit: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // the limit bounds the scanned window, not the purged count
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::PurgeZeroDonations {
+            start_after: None,
+            limit: Some(2),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "1");
+        assert_eq!(res.attributes[2].value, "1");
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::PurgeZeroDonations {
+            start_after: Some(1),
+            limit: Some(3),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "2");
+        assert_eq!(res.attributes[2].value, "4");
+
+        // unindexed legacy donations leave the donator count alone
+        assert_eq!(DONATOR_COUNT.load(deps.as_ref().storage).unwrap(), 2);
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::PurgeZeroDonations {
+            start_after: Some(4),
+            limit: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "1");
+        assert_eq!(res.attributes[2].value, "6");
+
+        let ids = DONATIONS
+            .keys(deps.as_ref().storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<u64>>>()
+            .unwrap();
+        assert_eq!(ids, vec![1, 4, 5]);
+
+        // carol was counted and is gone, bob remains
+        assert_eq!(DONATOR_COUNT.load(deps.as_ref().storage).unwrap(), 1);
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn debug_dump() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for _ in 0..3 {
+            let info = mock_info("alice", &coins(10, "uosmo"));
+            let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+
+        let msg = QueryMsg::DebugDump {
+            start_after: Some(0),
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: DebugDumpResponse = from_binary(&res).unwrap();
+        assert_eq!(value.entries.len(), 2);
+
+        // length-prefixed "donations" namespace followed by the big-endian id
+        for (entry, id) in value.entries.iter().zip(1_u64..) {
+            assert_eq!(entry.donation.id, id);
+            assert_eq!(entry.key, format!("0009646f6e6174696f6e73{:016x}", id));
+        }
+    }
+
+    #[test]
+    fn refund_prefixed_event() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let instantiate_msg = |event_prefix: &str| InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: Some(100),
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: Some(event_prefix.to_string()),
+        };
+
+        // slashes would read as a path, not an event type
+        let info = mock_info("admin", &[]);
+        let msg = instantiate_msg("campaign/6");
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidEventPrefix {}));
+
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            instantiate_msg("campaign6"),
+        )
+        .unwrap();
+
+        let info = mock_info("alice", &coins(100, "uosmo"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        let info = mock_info("alice", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::RefundAll {}).unwrap();
+
+        // refund event carries the retained fee
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "campaign6_refund_all");
+        assert_eq!(res.events[0].attributes, res.attributes);
+        assert!(res.events[0]
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "fee" && attr.value == "1uosmo"));
+    }
+
+    #[test]
+    fn withdraw_fees_pays_admin() {
+        let mut app = App::new(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked("alice"), coins(100, "uosmo"))
+                .unwrap();
+        });
+        let ctf_id = app.store_code(Box::new(ContractWrapper::new(
+            crate::contract::execute,
+            crate::contract::instantiate,
+            crate::contract::query,
+        )));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: Some(500),
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let ctf_addr = app
+            .instantiate_contract(ctf_id, Addr::unchecked("admin"), &msg, &[], "ctf6", None)
+            .unwrap();
+
+        // alice reclaims her donation, 5% stays in the contract
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            ctf_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            &coins(100, "uosmo"),
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            ctf_addr.clone(),
+            &ExecuteMsg::RefundAll {},
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            app.wrap().query_balance(&ctf_addr, "uosmo").unwrap().amount,
+            Uint128::from(5_u64)
+        );
+
+        // only the admin collects fees
+        let err = app
+            .execute_contract(
+                Addr::unchecked("alice"),
+                ctf_addr.clone(),
+                &ExecuteMsg::WithdrawFees {},
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Unauthorized {}
+        ));
+
+        app.execute_contract(
+            Addr::unchecked("admin"),
+            ctf_addr.clone(),
+            &ExecuteMsg::WithdrawFees {},
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            app.wrap().query_balance("admin", "uosmo").unwrap().amount,
+            Uint128::from(5_u64)
+        );
+        assert_eq!(
+            app.wrap().query_balance(&ctf_addr, "uosmo").unwrap().amount,
+            Uint128::zero()
+        );
+
+        // fees are only paid once
+        let err = app
+            .execute_contract(
+                Addr::unchecked("admin"),
+                ctf_addr,
+                &ExecuteMsg::WithdrawFees {},
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.root_cause().to_string(),
+            "Generic error: Nothing to withdraw!"
+        );
+    }
+
+    #[test]
+    fn withdraw_rollback_on_failed_transfer() {
+        fn ctf_contract() -> Box<dyn Contract<Empty>> {
+            let contract = ContractWrapper::new(
+                crate::contract::execute,
+                crate::contract::instantiate,
+                crate::contract::query,
+            )
+            .with_reply(crate::contract::reply);
+            Box::new(contract)
+        }
+
+        let mut app = App::new(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked("alice"), coins(100, "uosmo"))
+                .unwrap();
+        });
+        let ctf_id = app.store_code(ctf_contract());
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: Some(3600),
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let ctf_addr = app
+            .instantiate_contract(ctf_id, Addr::unchecked("admin"), &msg, &[], "ctf6", None)
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            ctf_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            &coins(100, "uosmo"),
+        )
+        .unwrap();
+
+        // simulate the transfer being rejected by draining the contract
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &ctf_addr, vec![])
+                .unwrap();
+        });
+
+        let res = app
+            .execute_contract(
+                Addr::unchecked("admin"),
+                ctf_addr.clone(),
+                &ExecuteMsg::Withdraw { limit: None },
+                &[],
+            )
+            .unwrap();
+        assert!(res.has_event(
+            &Event::new("wasm")
+                .add_attribute("method", "withdraw_rollback")
+                .add_attribute("restored", "1")
+        ));
+
+        // donation remains withdrawable
+        let donation: Donation = app
+            .wrap()
+            .query_wasm_smart(&ctf_addr, &QueryMsg::GetDonationInfo { id: 0 })
+            .unwrap();
+        assert!(!donation.withdrawn);
+
+        let value: AdminWithdrawableResponse = app
+            .wrap()
+            .query_wasm_smart(&ctf_addr, &QueryMsg::GetAdminWithdrawable {})
+            .unwrap();
+        assert_eq!(value.amounts, coins(100, "uosmo"));
+
+        // the failed sweep is not counted as withdrawn
+        let total: Vec<Coin> = app
+            .wrap()
+            .query_wasm_smart(&ctf_addr, &QueryMsg::GetTotalWithdrawn {})
+            .unwrap();
+        assert_eq!(total, vec![]);
+
+        // the failed sweep did not start the cooldown, so the admin retries right away
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &ctf_addr, coins(100, "uosmo"))
+                .unwrap();
+        });
+        app.execute_contract(
+            Addr::unchecked("admin"),
+            ctf_addr.clone(),
+            &ExecuteMsg::Withdraw { limit: None },
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            app.wrap().query_balance("admin", "uosmo").unwrap().amount,
+            Uint128::from(100_u64)
+        );
+
+        // a settled withdrawal leaves nothing pending
+        let pending = app
+            .wrap()
+            .query_wasm_raw(&ctf_addr, b"pending_withdrawal".to_vec())
+            .unwrap();
+        assert_eq!(pending, None);
+    }
+
+    #[test]
+    fn withdraw_rollback_single_denom() {
+        fn ctf_contract() -> Box<dyn Contract<Empty>> {
+            let contract = ContractWrapper::new(
+                crate::contract::execute,
+                crate::contract::instantiate,
+                crate::contract::query,
+            )
+            .with_reply(crate::contract::reply);
+            Box::new(contract)
+        }
+
+        let mut app = App::new(|router, _, storage| {
+            router
+                .bank
+                .init_balance(
+                    storage,
+                    &Addr::unchecked("alice"),
+                    vec![Coin::new(100, "uosmo"), Coin::new(50, "uusd")],
+                )
+                .unwrap();
+        });
+        let ctf_id = app.store_code(ctf_contract());
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: Some(vec!["uosmo".to_string(), "uusd".to_string()]),
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let ctf_addr = app
+            .instantiate_contract(ctf_id, Addr::unchecked("admin"), &msg, &[], "ctf6", None)
+            .unwrap();
+
+        for funds in [coins(100, "uosmo"), coins(50, "uusd")] {
+            app.execute_contract(
+                Addr::unchecked("alice"),
+                ctf_addr.clone(),
+                &ExecuteMsg::Deposit {},
+                &funds,
+            )
+            .unwrap();
+        }
+
+        // only the uusd transfer is rejected
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &ctf_addr, coins(100, "uosmo"))
+                .unwrap();
+        });
+
+        let res = app
+            .execute_contract(
+                Addr::unchecked("admin"),
+                ctf_addr.clone(),
+                &ExecuteMsg::Withdraw { limit: None },
+                &[],
+            )
+            .unwrap();
+        assert!(res.has_event(
+            &Event::new("wasm")
+                .add_attribute("method", "withdraw_rollback")
+                .add_attribute("restored", "1")
+        ));
+        assert_eq!(
+            app.wrap().query_balance("admin", "uosmo").unwrap().amount,
+            Uint128::from(100_u64)
+        );
+
+        // only the uusd donation remains withdrawable
+        let value: AdminWithdrawableResponse = app
+            .wrap()
+            .query_wasm_smart(&ctf_addr, &QueryMsg::GetAdminWithdrawable {})
+            .unwrap();
+        assert_eq!(value.amounts, coins(50, "uusd"));
+
+        // only the settled uosmo transfer counts as withdrawn
+        let total: Vec<Coin> = app
+            .wrap()
+            .query_wasm_smart(&ctf_addr, &QueryMsg::GetTotalWithdrawn {})
+            .unwrap();
+        assert_eq!(total, coins(100, "uosmo"));
+
+        // the last transfer reply clears the pending withdrawal
+        let pending = app
+            .wrap()
+            .query_wasm_raw(&ctf_addr, b"pending_withdrawal".to_vec())
+            .unwrap();
+        assert_eq!(pending, None);
+    }
+
+    #[test]
+    fn stats() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // alice donates twice, bob once
+        for (donator, amount) in [("alice", 10_u128), ("bob", 20), ("alice", 30)] {
+            let info = mock_info(donator, &coins(amount, "uosmo"));
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value,
+            StatsResponse {
+                total_donations: 3,
+                total_amount: coins(60, "uosmo"),
+                withdrawn_amount: vec![],
+                donator_count: 2,
+            }
+        );
+
+        // withdrawn donations are tracked separately
+        let info = mock_info("admin", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Withdraw { limit: None },
+        )
+        .unwrap();
+        let info = mock_info("bob", &coins(5, "uosmo"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.total_donations, 4);
+        assert_eq!(value.total_amount, coins(65, "uosmo"));
+        assert_eq!(value.withdrawn_amount, coins(60, "uosmo"));
+        assert_eq!(value.donator_count, 2);
+
+        // refunds keep counting towards every donation made, like the donation count
+        let info = mock_info("bob", &[]);
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::RefundAll {}).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.total_donations, 4);
+        assert_eq!(value.total_amount, coins(65, "uosmo"));
+        assert_eq!(value.withdrawn_amount, coins(60, "uosmo"));
+        assert_eq!(value.donator_count, 2);
+    }
+
+    #[test]
+    fn top_donation() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: Some(vec!["uosmo".to_string(), "uusd".to_string()]),
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let top_donation = |deps: Deps, denom: &str| -> Option<Donation> {
+            let msg = QueryMsg::GetTopDonation {
+                denom: denom.to_string(),
+            };
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+        };
+
+        // nothing before any donation
+        assert_eq!(top_donation(deps.as_ref(), "uosmo"), None);
+
+        for (donator, amount, denom) in [
+            ("alice", 10_u128, "uosmo"),
+            ("bob", 30, "uosmo"),
+            ("carol", 20, "uosmo"),
+            ("erin", 100, "uusd"),
+            ("dave", 30, "uosmo"),
+        ] {
+            let info = mock_info(donator, &coins(amount, denom));
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+
+        // bob and dave tie, bob donated first, the uusd donation is not compared
+        let value = top_donation(deps.as_ref(), "uosmo").unwrap();
+        assert_eq!(value.id, 1);
+        assert_eq!(value.donator, "bob");
+        assert_eq!(value.amount, Uint128::from(30_u64));
+
+        let value = top_donation(deps.as_ref(), "uusd").unwrap();
+        assert_eq!(value.donator, "erin");
+    }
+
+    #[test]
+    fn average_donation() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: Some(vec!["uosmo".to_string(), "uusd".to_string()]),
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let average = |deps: Deps, denom: &str| -> Uint128 {
+            let msg = QueryMsg::GetAverageDonation {
+                denom: denom.to_string(),
+            };
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+        };
+
+        // zero before any donation
+        assert_eq!(average(deps.as_ref(), "uosmo"), Uint128::zero());
+
+        for (donator, amount, denom) in [
+            ("alice", 10_u128, "uosmo"),
+            ("bob", 25, "uosmo"),
+            ("bob", 7, "uusd"),
+            ("alice", 30, "uosmo"),
+        ] {
+            let info = mock_info(donator, &coins(amount, denom));
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+
+        // 65 / 3 truncates to 21, denoms are averaged separately
+        assert_eq!(average(deps.as_ref(), "uosmo"), Uint128::from(21_u64));
+        assert_eq!(average(deps.as_ref(), "uusd"), Uint128::from(7_u64));
+        assert_eq!(average(deps.as_ref(), "ujuno"), Uint128::zero());
+    }
+
+    #[test]
+    fn refund_all() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // first donation gets withdrawn by the admin
+        let info = mock_info("alice", &coins(10, "uosmo"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        let info = mock_info("admin", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Withdraw { limit: None },
+        )
+        .unwrap();
+
+        // two pending donations for alice, one for bob
+        for (donator, amount) in [("alice", 20_u128), ("bob", 40), ("alice", 30)] {
+            let info = mock_info(donator, &coins(amount, "uosmo"));
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+
+        // only the pending amounts come back
+        let info = mock_info("alice", &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::RefundAll {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(50, "uosmo"),
+            })
+        );
+        assert_eq!(res.attributes[2].value, "2");
+
+        // withdrawn donation stays on record, refunded ones are gone
+        assert!(DONATIONS.has(deps.as_ref().storage, 0));
+        assert!(!DONATIONS.has(deps.as_ref().storage, 1));
+        assert!(DONATIONS.has(deps.as_ref().storage, 2));
+        assert!(!DONATIONS.has(deps.as_ref().storage, 3));
+
+        // nothing left to refund
+        let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::RefundAll {}).unwrap_err();
+        assert_eq!(err.to_string(), "Generic error: Nothing to refund!");
+    }
+
+    #[test]
+    fn refund_fee() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        // fee is capped
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: Some(501),
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::RefundFeeTooHigh { max: 500 }));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: Some(250),
+            withdraw_cooldown: None,
+            accepted_denoms: Some(vec!["uosmo".to_string(), "uusd".to_string()]),
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for funds in [coins(1000, "uosmo"), coins(200, "uusd")] {
+            let info = mock_info("alice", &funds);
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+
+        // 2.5% of the refund stays in the contract
+        let info = mock_info("alice", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::RefundAll {}).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(975, "uosmo"),
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(195, "uusd"),
+            })
+        );
+
+        // fees are kept per denom
+        assert_eq!(res.attributes[4].key, "fee");
+        assert_eq!(res.attributes[4].value, "25uosmo,5uusd");
+        assert_eq!(
+            RETAINED_FEES.load(deps.as_ref().storage, "uosmo").unwrap(),
+            Uint128::from(25_u64)
+        );
+        assert_eq!(
+            RETAINED_FEES.load(deps.as_ref().storage, "uusd").unwrap(),
+            Uint128::from(5_u64)
+        );
+    }
+
+    #[test]
+    fn recent_donations() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        for amount in 1..=5_u128 {
+            let info = mock_info("alice", &coins(amount, "uosmo"));
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+
+        // newest first
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetRecentDonations { count: 3 },
+        )
+        .unwrap();
+        let value: AllDonations = from_binary(&res).unwrap();
+        let ids: Vec<u64> = value.donations.iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![4, 3, 2]);
+        assert_eq!(value.donations[0].amount, Uint128::from(5_u64));
+
+        // count beyond the total returns everything
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetRecentDonations { count: 100 },
+        )
+        .unwrap();
+        let value: AllDonations = from_binary(&res).unwrap();
+        assert_eq!(value.donations.len(), 5);
+    }
+
+    #[test]
+    fn withdraw_scans_pending_index() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let pending_ids = |deps: Deps| -> Vec<u64> {
+            PENDING_DONATIONS
+                .keys(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<u64>>>()
+                .unwrap()
+        };
+
+        for amount in [10_u128, 20] {
+            let info = mock_info("alice", &coins(amount, "uosmo"));
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+        assert_eq!(pending_ids(deps.as_ref()), vec![0, 1]);
+
+        // first withdrawal empties the index
+        let info = mock_info("admin", &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Withdraw { limit: None },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[1].value, "30uosmo");
+        assert!(pending_ids(deps.as_ref()).is_empty());
+
+        // only new donations are pending
+        for amount in [5_u128, 7] {
+            let info = mock_info("bob", &coins(amount, "uosmo"));
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+        assert_eq!(pending_ids(deps.as_ref()), vec![2, 3]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Withdraw { limit: None },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[1].value, "12uosmo");
+        assert!(pending_ids(deps.as_ref()).is_empty());
+    }
+
+    #[test]
+    fn instantiated_at() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
+        let info = mock_info("admin", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // recorded time does not move with later blocks
+        let mut later = mock_env();
+        later.block.time = later.block.time.plus_seconds(3600);
+        let res = query(deps.as_ref(), later, QueryMsg::GetInstantiatedAt {}).unwrap();
+        let value: u64 = from_binary(&res).unwrap();
+        assert_eq!(value, mock_env().block.time.seconds());
+    }
+
     #[test]
     fn exploit() {
         let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            campaign_start: None,
+            campaign_end: None,
+            min_donation: None,
+            refund_fee_bps: None,
+            withdraw_cooldown: None,
+            accepted_denoms: None,
+            denom_min_donations: None,
+            event_prefix: None,
+        };
         let info = mock_info("admin", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -288,7 +2759,7 @@ mod tests {
 
         // admin unable to withdraw donations
         let info = mock_info("admin", &[]);
-        let msg = ExecuteMsg::Withdraw {};
+        let msg = ExecuteMsg::Withdraw { limit: None };
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     }
 }
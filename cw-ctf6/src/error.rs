@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,6 +9,33 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Campaign not started")]
+    CampaignNotStarted {},
+
+    #[error("Campaign ended")]
+    CampaignEnded {},
+
+    #[error("Invalid campaign window")]
+    InvalidCampaignWindow {},
+
+    #[error("Donation below minimum of {min}")]
+    DonationTooSmall { min: Uint128 },
+
+    #[error("Refund fee above maximum of {max} bps")]
+    RefundFeeTooHigh { max: u64 },
+
+    #[error("Withdraw cooldown active, retry after {retry_after}")]
+    CooldownActive { retry_after: u64 },
+
+    #[error("No accepted denoms")]
+    NoAcceptedDenoms {},
+
+    #[error("Donation already withdrawn")]
+    DonationWithdrawn {},
+
+    #[error("Invalid event prefix")]
+    InvalidEventPrefix {},
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
     // Add any other custom errors you like here.
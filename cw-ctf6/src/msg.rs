@@ -1,16 +1,46 @@
+use cosmwasm_std::{Addr, Coin, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::state::Donation;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    pub campaign_start: Option<u64>,
+    pub campaign_end: Option<u64>,
+    pub min_donation: Option<Uint128>,
+    pub refund_fee_bps: Option<u64>,
+    pub withdraw_cooldown: Option<u64>,
+    pub accepted_denoms: Option<Vec<String>>,
+    pub denom_min_donations: Option<Vec<(String, Uint128)>>,
+    pub event_prefix: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     Deposit {},
-    Withdraw {},
+    Withdraw {
+        limit: Option<u32>,
+    },
+    SetMinDonation {
+        amount: Uint128,
+    },
+    RefundAll {},
+    ReserveDonation {
+        id: u64,
+    },
+    UnreserveDonation {
+        id: u64,
+    },
+    PurgeZeroDonations {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    WithdrawFees {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -29,5 +59,85 @@ pub struct AllDonations {
 pub enum QueryMsg {
     GetNextDonationId {},
     GetAllDonations {},
-    GetDonationInfo { id: u64 },
+    GetDonationInfo {
+        id: u64,
+    },
+    GetDonationsByDonator {
+        donator: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    GetDonationsAbove {
+        denom: String,
+        threshold: Uint128,
+        limit: Option<u32>,
+    },
+    CanWithdrawAll {},
+    GetAdminWithdrawable {},
+    GetConfig {},
+    GetStats {},
+    GetAverageDonation {
+        denom: String,
+    },
+    GetRecentDonations {
+        count: u32,
+    },
+    GetInstantiatedAt {},
+    GetTopDonation {
+        denom: String,
+    },
+    GetTotalWithdrawn {},
+    #[cfg(feature = "debug")]
+    DebugDump {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[cfg(feature = "debug")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DebugEntry {
+    /// hex-encoded raw storage key
+    pub key: String,
+    pub donation: Donation,
+}
+
+#[cfg(feature = "debug")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DebugDumpResponse {
+    pub entries: Vec<DebugEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatsResponse {
+    /// every donation ever made, including refunded and purged ones
+    pub total_donations: u64,
+    /// amount of every donation ever made, per denom
+    pub total_amount: Vec<Coin>,
+    /// amount swept by admin withdrawals, per denom
+    pub withdrawn_amount: Vec<Coin>,
+    /// donators with at least one donation still on record
+    pub donator_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub admin: Addr,
+    pub campaign_start: Option<u64>,
+    pub campaign_end: Option<u64>,
+    pub min_donation: Uint128,
+    pub refund_fee_bps: u64,
+    pub accepted_denoms: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AdminWithdrawableResponse {
+    pub amounts: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CanWithdrawAllResponse {
+    pub safe: bool,
+    pub outstanding_count: u64,
+    pub recommended_batches: u32,
 }
@@ -13,13 +13,89 @@ pub struct Donation {
     pub amount: Uint128,
     /// bool to indicate whether donation amount is withdrawn or not
     pub withdrawn: bool,
+    /// donation time in seconds, zero for donations made before this field existed
+    #[serde(default)]
+    pub created_at: u64,
+    /// bool to indicate whether donation is reserved for a refund to the donator
+    #[serde(default)]
+    pub reserved: bool,
+    /// donated denom, uosmo for donations made before multiple denoms were accepted
+    #[serde(default = "default_denom")]
+    pub denom: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingWithdrawal {
+    /// donation ids marked withdrawn
+    pub ids: Vec<u64>,
+    /// last withdrawal time before this one, restored if a transfer fails
+    pub last_withdraw: Option<u64>,
+}
+
+/// the only denom accepted before multiple denoms were supported
+pub fn default_denom() -> String {
+    "uosmo".to_string()
 }
 
 /// store admin address
 pub const ADMIN: Item<Addr> = Item::new("admin_addr");
 
+/// instantiation block time in seconds, zero for contracts instantiated before this existed
+pub const INSTANTIATED_AT: Item<u64> = Item::new("instantiated_at");
+
+/// donations are rejected before this time, in seconds
+pub const CAMPAIGN_START: Item<u64> = Item::new("campaign_start");
+
+/// donations are rejected after this time, in seconds
+pub const CAMPAIGN_END: Item<u64> = Item::new("campaign_end");
+
+/// default denom donations below this amount are rejected, unless `DENOM_MIN_DONATIONS` lists it
+pub const MIN_DONATION: Item<Uint128> = Item::new("min_donation");
+
+/// fee charged on donator refunds, in basis points
+pub const REFUND_FEE_BPS: Item<u64> = Item::new("refund_fee_bps");
+
+/// refund fees kept by the contract, per denom
+pub const RETAINED_FEES: Map<&str, Uint128> = Map::new("retained_fees");
+
+/// denoms accepted as donations, uosmo only when unset
+pub const ACCEPTED_DENOMS: Item<Vec<String>> = Item::new("accepted_denoms");
+
+/// per-denom minimum donation, overriding `MIN_DONATION` for listed denoms, unlisted denoms have none
+pub const DENOM_MIN_DONATIONS: Map<&str, Uint128> = Map::new("denom_min_donations");
+
+/// minimum seconds between admin withdrawals
+pub const WITHDRAW_COOLDOWN: Item<u64> = Item::new("withdraw_cooldown");
+
+/// block time of the last admin withdrawal, in seconds
+pub const LAST_WITHDRAW: Item<u64> = Item::new("last_withdraw");
+
+/// cumulative amount swept by admin withdrawals, per denom
+pub const TOTAL_WITHDRAWN: Map<&str, Uint128> = Map::new("total_withdrawn");
+
+/// running total donated per denom, never reduced by refunds or withdrawals
+pub const DENOM_DONATED: Map<&str, Uint128> = Map::new("denom_donated");
+
+/// running number of donations per denom
+pub const DENOM_DONATION_COUNT: Map<&str, u64> = Map::new("denom_donation_count");
+
 /// increment as donation identifier
 pub const DONATION_COUNT: Item<u64> = Item::new("donation_count");
 
+/// number of distinct donator addresses
+pub const DONATOR_COUNT: Item<u64> = Item::new("donator_count");
+
 /// donation id to donation struct
 pub const DONATIONS: Map<u64, Donation> = Map::new("donations");
+
+/// ids of donations neither withdrawn nor reserved, scanned by admin withdrawals
+pub const PENDING_DONATIONS: Map<u64, ()> = Map::new("pending_donations");
+
+/// (donator address, donation id) index for per-user lookups
+pub const DONATIONS_BY_USER: Map<(&Addr, u64), ()> = Map::new("donations_by_user");
+
+/// admin withdrawal awaiting its transfer replies, used to roll back on failure
+pub const PENDING_WITHDRAWAL: Item<PendingWithdrawal> = Item::new("pending_withdrawal");
+
+/// prefix for custom event types, empty when not configured
+pub const EVENT_PREFIX: Item<String> = Item::new("event_prefix");